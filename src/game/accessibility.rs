@@ -0,0 +1,127 @@
+//! Options making the game easier to see and more comfortable to play.
+use std::sync::RwLock;
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+static ACCESSIBILITY: RwLock<Accessibility> = RwLock::new(Accessibility::new());
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Multiplier of the screen shake strength. Zero disables it.
+    pub screen_shake: f32,
+    /// Tones down flashing hits, damage and screen effects.
+    pub reduce_flashes: bool,
+    /// Outlines projectiles in black so they stand out from the background.
+    pub high_contrast: bool,
+    pub palette: Palette,
+    /// Multiplier of the size of titles and other text.
+    pub text_scale: f32,
+}
+
+impl Accessibility {
+    const fn new() -> Self {
+        Self {
+            screen_shake: 1.0,
+            reduce_flashes: false,
+            high_contrast: false,
+            palette: Palette::Default,
+            text_scale: 1.0,
+        }
+    }
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Colors of the health bar and projectiles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Orange and blue for protanopia and deuteranopia.
+    RedGreen,
+    /// Red and teal for tritanopia.
+    BlueYellow,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Default, Palette::RedGreen, Palette::BlueYellow];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::RedGreen => "red_green",
+            Palette::BlueYellow => "blue_yellow",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|palette| palette.name() == name)
+    }
+
+    /// The color of the health bar at the given fraction of the maximum health.
+    pub fn health(&self, fraction: f32) -> Color {
+        let (empty, full) = match self {
+            Palette::Default => (
+                Color::from_rgb(0.5, 0.04, 0.05),
+                Color::from_rgb(0.49, 0.886, 0.643),
+            ),
+            Palette::RedGreen => (
+                Color::from_rgb(0.84, 0.37, 0.0),
+                Color::from_rgb(0.34, 0.71, 0.91),
+            ),
+            Palette::BlueYellow => (
+                Color::from_rgb(0.8, 0.1, 0.1),
+                Color::from_rgb(0.0, 0.62, 0.45),
+            ),
+        };
+        empty.lerp(full, fraction)
+    }
+
+    /// The color of projectiles shot by enemies.
+    pub fn hostile(&self) -> Color {
+        match self {
+            Palette::Default => Color::WHITE,
+            Palette::RedGreen => Color::from_rgb(0.9, 0.6, 0.0),
+            Palette::BlueYellow => Color::from_rgb(0.9, 0.2, 0.2),
+        }
+    }
+
+    /// The color of projectiles returned by the paddle.
+    pub fn friendly(&self) -> Color {
+        match self {
+            Palette::Default => Color::WHITE,
+            Palette::RedGreen => Color::from_rgb(0.34, 0.71, 0.91),
+            Palette::BlueYellow => Color::from_rgb(0.0, 0.75, 0.7),
+        }
+    }
+
+    /// The color of projectiles returned with a hard shot.
+    pub fn critical(&self) -> Color {
+        match self {
+            Palette::Default => Color::WHITE,
+            Palette::RedGreen => Color::from_rgb(0.95, 0.9, 0.25),
+            Palette::BlueYellow => Color::from_rgb(0.9, 0.6, 0.8),
+        }
+    }
+}
+
+/// The accessibility options currently in use.
+pub fn get() -> Accessibility {
+    *ACCESSIBILITY.read().unwrap()
+}
+
+/// Applies the options to everything updated or created from now on.
+pub fn set(accessibility: Accessibility) {
+    *ACCESSIBILITY.write().unwrap() = accessibility;
+}
+
+/// The size of text scaled by the text scale option.
+pub fn text_size(size: Vec2) -> Vec2 {
+    size * get().text_scale
+}
@@ -1,11 +1,20 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, time::Duration};
 
 use let_engine::{
     egui::{self, RichText, TextEdit, TextStyle, TopBottomPanel},
+    prelude::vec2,
     SETTINGS,
 };
 
-use super::{Fullscreen, GameSettings, Message};
+use super::{
+    accessibility::{Accessibility, Palette},
+    game_loop::{Run, StartAt},
+    layout::Layout,
+    locale::Language,
+    stages::{Stage, STAGES},
+    DebugView, Fullscreen, GameScene, GameSettings, Message,
+};
+use crate::objects::transition::Transition;
 
 #[derive(Clone, Debug)]
 pub struct Console {
@@ -103,6 +112,88 @@ impl Console {
                 self.print(format!("vsync set: {} -> {}", self.settings.vsync, vsync));
                 return Some(Message::ApplySettings(settings));
             }
+            "pause_on_focus_loss" => {
+                let Some(command) = tokens.next() else {
+                    self.print(format!(
+                        "usage:\n  pause_on_focus_loss [on/off]\npause_on_focus_loss={}",
+                        self.settings.pause_on_focus_loss
+                    ));
+                    return None;
+                };
+                let pause_on_focus_loss = match command {
+                    "on" => true,
+                    "true" => true,
+                    "enable" => true,
+                    "disable" => false,
+                    "false" => false,
+                    "off" => false,
+                    e => {
+                        self.print(format!("You can not set pause_on_focus_loss to \"{e}\"."));
+                        return None;
+                    }
+                };
+                let settings = GameSettings {
+                    pause_on_focus_loss,
+                    ..self.settings
+                };
+                self.print(format!(
+                    "pause_on_focus_loss set: {} -> {}",
+                    self.settings.pause_on_focus_loss, pause_on_focus_loss
+                ));
+                return Some(Message::ApplySettings(settings));
+            }
+            "trajectory" => {
+                let Some(command) = tokens.next() else {
+                    self.print(format!(
+                        "usage:\n  trajectory [seconds]\n trajectory={}",
+                        self.settings.trajectory_preview
+                    ));
+                    return None;
+                };
+                let Ok(trajectory_preview) = command.parse::<f32>() else {
+                    self.print(format!("You can not preview \"{command}\" seconds."));
+                    return None;
+                };
+                let settings = GameSettings {
+                    trajectory_preview: trajectory_preview.max(0.0),
+                    ..self.settings
+                };
+                self.print(format!(
+                    "trajectory preview set: {} -> {}",
+                    self.settings.trajectory_preview, settings.trajectory_preview
+                ));
+                return Some(Message::ApplySettings(settings));
+            }
+            "aim_assist" => {
+                let Some(command) = tokens.next() else {
+                    self.print(format!(
+                        "usage:\n  aim_assist [on/off]\naim_assist={}",
+                        self.settings.aim_assist
+                    ));
+                    return None;
+                };
+                let aim_assist = match command {
+                    "on" => true,
+                    "true" => true,
+                    "enable" => true,
+                    "disable" => false,
+                    "false" => false,
+                    "off" => false,
+                    e => {
+                        self.print(format!("You can not set aim_assist to \"{e}\"."));
+                        return None;
+                    }
+                };
+                let settings = GameSettings {
+                    aim_assist,
+                    ..self.settings
+                };
+                self.print(format!(
+                    "aim_assist set: {} -> {}",
+                    self.settings.aim_assist, aim_assist
+                ));
+                return Some(Message::ApplySettings(settings));
+            }
             "fps_limit" => {
                 let Some(command) = tokens.next() else {
                     self.print(format!(
@@ -151,30 +242,273 @@ impl Console {
                 };
                 return Some(Message::ApplySettings(settings));
             }
+            "language" => {
+                let Some(command) = tokens.next() else {
+                    let codes = Language::ALL.map(|language| language.code()).join("/");
+                    self.print(format!(
+                        "usage:\n  language [{codes}]\nlanguage={}",
+                        self.settings.language.code()
+                    ));
+                    return None;
+                };
+                let Some(language) = Language::from_code(command) else {
+                    self.print(format!("There is no language \"{command}\"."));
+                    return None;
+                };
+                let settings = GameSettings {
+                    language,
+                    ..self.settings
+                };
+                self.print(format!(
+                    "language set: {} -> {}",
+                    self.settings.language.code(),
+                    language.code()
+                ));
+                return Some(Message::ApplySettings(settings));
+            }
+            "accessibility" => {
+                let accessibility = self.settings.accessibility;
+                let (Some(option), Some(value)) = (tokens.next(), tokens.next()) else {
+                    self.print(format!(
+                        "usage:\n  accessibility [option] [value]\n shake={}\n reduce_flashes={}\n high_contrast={}\n palette={}\n text_scale={}",
+                        accessibility.screen_shake,
+                        accessibility.reduce_flashes,
+                        accessibility.high_contrast,
+                        accessibility.palette.name(),
+                        accessibility.text_scale,
+                    ));
+                    return None;
+                };
+                let switch = match value {
+                    "on" | "true" | "enable" => Some(true),
+                    "off" | "false" | "disable" => Some(false),
+                    _ => None,
+                };
+                let accessibility = match (option, switch) {
+                    ("shake", _) => match value.parse::<f32>() {
+                        Ok(shake) => Accessibility {
+                            screen_shake: shake.clamp(0.0, 2.0),
+                            ..accessibility
+                        },
+                        Err(_) => {
+                            self.print(format!(
+                                "You can not shake the screen \"{value}\" times as much."
+                            ));
+                            return None;
+                        }
+                    },
+                    ("reduce_flashes", Some(reduce_flashes)) => Accessibility {
+                        reduce_flashes,
+                        ..accessibility
+                    },
+                    ("high_contrast", Some(high_contrast)) => Accessibility {
+                        high_contrast,
+                        ..accessibility
+                    },
+                    ("palette", _) => match Palette::from_name(value) {
+                        Some(palette) => Accessibility {
+                            palette,
+                            ..accessibility
+                        },
+                        None => {
+                            let names = Palette::ALL.map(|palette| palette.name()).join("/");
+                            self.print(format!("There is no palette \"{value}\". Try {names}."));
+                            return None;
+                        }
+                    },
+                    ("text_scale", _) => match value.parse::<f32>() {
+                        Ok(text_scale) => Accessibility {
+                            text_scale: text_scale.clamp(0.5, 2.0),
+                            ..accessibility
+                        },
+                        Err(_) => {
+                            self.print(format!("You can not scale text by \"{value}\"."));
+                            return None;
+                        }
+                    },
+                    ("reduce_flashes" | "high_contrast", None) => {
+                        self.print(format!("You can not set {option} to \"{value}\"."));
+                        return None;
+                    }
+                    _ => {
+                        self.print(format!("There is no accessibility option \"{option}\"."));
+                        return None;
+                    }
+                };
+                self.print(format!("accessibility {option} set to {value}"));
+                return Some(Message::ApplySettings(GameSettings {
+                    accessibility,
+                    ..self.settings
+                }));
+            }
+            "layout" => {
+                let layout = self.settings.layout;
+                let (Some(option), Some(value)) = (tokens.next(), tokens.next()) else {
+                    self.print(format!(
+                        "usage:\n  layout [option] [value]\n ui_scale={}\n safe_area={}\n resolution={}x{}",
+                        layout.ui_scale,
+                        layout.safe_area,
+                        self.settings.resolution.x,
+                        self.settings.resolution.y,
+                    ));
+                    return None;
+                };
+                let settings = match option {
+                    "ui_scale" => match value.parse::<f32>() {
+                        Ok(ui_scale) => GameSettings {
+                            layout: Layout {
+                                ui_scale: ui_scale.clamp(0.5, 2.0),
+                                ..layout
+                            },
+                            ..self.settings
+                        },
+                        Err(_) => {
+                            self.print(format!("You can not scale the UI by \"{value}\"."));
+                            return None;
+                        }
+                    },
+                    "safe_area" => match value.parse::<f32>() {
+                        Ok(safe_area) => GameSettings {
+                            layout: Layout {
+                                safe_area: safe_area.clamp(0.0, 0.2),
+                                ..layout
+                            },
+                            ..self.settings
+                        },
+                        Err(_) => {
+                            self.print(format!("You can not keep \"{value}\" of the screen free."));
+                            return None;
+                        }
+                    },
+                    "resolution" => {
+                        let resolution = value
+                            .split_once('x')
+                            .and_then(|(x, y)| {
+                                Some((x.parse::<f32>().ok()?, y.parse::<f32>().ok()?))
+                            })
+                            .filter(|(x, y)| *x > 0.0 && *y > 0.0);
+                        let Some((x, y)) = resolution else {
+                            self.print(format!("\"{value}\" is not a resolution like 455x256."));
+                            return None;
+                        };
+                        GameSettings {
+                            resolution: vec2(x, y),
+                            ..self.settings
+                        }
+                    }
+                    _ => {
+                        self.print(format!("There is no layout option \"{option}\"."));
+                        return None;
+                    }
+                };
+                self.print(format!("layout {option} set to {value}"));
+                return Some(Message::ApplySettings(settings));
+            }
             "scene" => {
                 let Some(command) = tokens.next() else {
-                    self.print("usage:\n  scene [scene]".to_string());
+                    self.print("usage:\n  scene [scene] [instant/fade/wipe/dissolve]".to_string());
                     return None;
                 };
-                match command {
-                    "menu" => return Some(Message::SwitchScene(super::GameScene::Menu)),
-                    "ingame" => return Some(Message::SwitchScene(super::GameScene::Ingame)),
-                    e => self.print(format!("There is no scene called \"{e}\".")),
+                let scene = match command {
+                    "menu" => GameScene::Menu,
+                    "ingame" => GameScene::Ingame,
+                    "stages" => GameScene::StageSelect,
+                    "credits" => GameScene::Credits,
+                    "statistics" => GameScene::Statistics,
+                    e => {
+                        self.print(format!("There is no scene called \"{e}\"."));
+                        return None;
+                    }
                 };
-                return None;
+                let duration = Duration::from_millis(600);
+                let transition = match tokens.next() {
+                    None => scene.transition(),
+                    Some("instant") => Transition::Instant,
+                    Some("fade") => Transition::Fade(duration),
+                    Some("wipe") => Transition::Wipe(duration),
+                    Some("dissolve") => Transition::Dissolve(duration),
+                    Some(e) => {
+                        self.print(format!("There is no transition called \"{e}\"."));
+                        return None;
+                    }
+                };
+                return Some(Message::SwitchSceneWith(scene, transition));
+            }
+            "endless" => {
+                let seed = match tokens.next() {
+                    None => rand::random(),
+                    Some(seed) => {
+                        let Ok(seed) = seed.parse() else {
+                            self.print(format!("\"{seed}\" is not a valid seed."));
+                            return None;
+                        };
+                        seed
+                    }
+                };
+                self.print(format!("Starting endless run with seed {seed}."));
+                return Some(Message::Play(Run::Endless(seed)));
             }
             "stage" => {
                 let Some(command) = tokens.next() else {
-                    self.print("usage:\n  stage [number]".to_string());
+                    self.print("usage:\n  stage [number/name] [@event/@label]".to_string());
                     return None;
                 };
-                let Ok(level) = command.parse() else {
-                    self.print(format!("Invalid stage \"{command}\"."));
+                let level = match command.parse() {
+                    Ok(level) => level,
+                    Err(_) => {
+                        let Some(level) = STAGES
+                            .iter()
+                            .position(|name| name.eq_ignore_ascii_case(command))
+                        else {
+                            self.print(format!("Invalid stage \"{command}\"."));
+                            return None;
+                        };
+                        level
+                    }
+                };
+                let Some(start) = tokens.next() else {
+                    return Some(Message::ChangeLevel(level));
+                };
+                let Some(start) = start.strip_prefix('@') else {
+                    self.print(format!("\"{start}\" has to start with @."));
                     return None;
                 };
-
-                return Some(Message::ChangeLevel(level));
+                let start = match start.parse() {
+                    Ok(index) => StartAt::Index(index),
+                    Err(_) => StartAt::Label(start.to_string()),
+                };
+                let stage = match Stage::load(level as u32) {
+                    Ok(Some(stage)) => stage,
+                    Ok(None) => {
+                        self.print(format!("There is no stage {level}."));
+                        return None;
+                    }
+                    Err(error) => {
+                        self.print(format!("Failed to load stage {level}: {error}"));
+                        return None;
+                    }
+                };
+                if let Err(error) = start.resolve(&stage.events) {
+                    self.print(error.to_string());
+                    return None;
+                }
+                self.print(format!("Playtesting {} from {start:?}.", stage.name));
+                return Some(Message::Play(Run::Playtest(stage, start)));
+            }
+            "debug" => {
+                let Some(command) = tokens.next() else {
+                    self.print("usage:\n  debug [colliders/perf/csv]".to_string());
+                    return None;
+                };
+                match command {
+                    "colliders" => return Some(Message::ToggleDebug(DebugView::Colliders)),
+                    "perf" => return Some(Message::ToggleDebug(DebugView::Performance)),
+                    "csv" => return Some(Message::ToggleDebug(DebugView::FrameLog)),
+                    e => self.print(format!("There is no debug view called \"{e}\".")),
+                };
+                return None;
             }
+            "editor" => return Some(Message::ToggleEditor),
             "quit" => return Some(Message::Exit),
             "exit" => return Some(Message::Exit),
             "close" => self.active = false,
@@ -202,9 +536,18 @@ const HELP_MESSAGE: &str = "
 Available commands:
   vsync [on/off] - Enables or disables vsync.
   fps_limit [number] - sets the framerate limit of the game.
+  pause_on_focus_loss [on/off] - pauses the game when the window loses focus.
+  trajectory [seconds] - sets how far returned shots are previewed. 0 disables it.
+  aim_assist [on/off] - slowly turns the arrow towards the nearest enemy.
   fullscreen [windowed/borderless/exclusive] - Sets if the window is in fullscreen.
-  scene [scene] - changes the scene.
-  stage [number] - sets the stage.
+  language [code] - sets the language of the game.
+  accessibility [option] [value] - sets screen shake, flashes, contrast, colors and text size.
+  layout [option] [value] - sets the ui scale, safe area margin and resolution the ui is laid out for.
+  scene [scene] [transition] - changes the scene.
+  stage [number/name] [@event/@label] - sets the stage or playtests it from the given event.
+  endless [seed] - starts an endless run with a random or the given seed.
+  debug [colliders/perf/csv] - toggles a debug overlay or logging frame stats to a csv file.
+  editor - opens or closes the stage editor.
   clear - clears the console
   clear-cache - clears the cache reducing memory usage.
   close - closes the terminal 
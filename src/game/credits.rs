@@ -0,0 +1,95 @@
+use crate::objects::button::Button;
+
+use super::{
+    accessibility, locale,
+    main_menu::{back_button, backdrop, scene_title},
+    GameScene, Layers, Message,
+};
+use anyhow::Result;
+use let_engine::prelude::*;
+
+/// Half the height of the box the credits get written in.
+const ROLL_HEIGHT: f32 = 2.0;
+/// Speed of the roll in screen heights per second.
+const ROLL_SPEED: f32 = 0.15;
+
+/// Rolls the attribution of the README over the screen.
+pub struct Credits {
+    backdrop: Object,
+    title: Label<Object>,
+    roll: Label<Object>,
+    back_button: Button,
+}
+
+impl Credits {
+    pub fn new(layers: &Layers) -> Result<Self> {
+        let backdrop = backdrop(layers)?;
+        let title = scene_title(layers, "scene.credits")?;
+
+        let roll = Label::new(
+            locale::font(),
+            LabelCreateInfo::default()
+                .text(credits_text())
+                .align(Direction::N)
+                .transform(Transform::default().position(vec2(0.0, 1.0 + ROLL_HEIGHT)))
+                .appearance(
+                    Appearance::default()
+                        .transform(Transform::default().size(vec2(1.5, ROLL_HEIGHT))),
+                )
+                .scale(accessibility::text_size(vec2(30.0, 30.0))),
+        )
+        .init(&layers.ui)?;
+
+        let back_button = back_button(layers)?;
+
+        if let Some(window) = SETTINGS.window() {
+            window.set_cursor_visible(true);
+        }
+
+        Ok(Self {
+            backdrop,
+            title,
+            roll,
+            back_button,
+        })
+    }
+
+    pub fn unload(self) {
+        let _ = self.backdrop.remove();
+        let _ = self.title.object.remove();
+        let _ = self.roll.object.remove();
+        self.back_button.remove();
+    }
+
+    pub fn update(&mut self) -> Result<Option<Message>> {
+        let mut message = None;
+
+        let position = &mut self.roll.object.transform.position;
+        position.y -= TIME.delta_time() as f32 * ROLL_SPEED * 2.0;
+        // Return to the menu once the whole box left the top of the screen.
+        if position.y < -1.0 - ROLL_HEIGHT {
+            message = Some(Message::SwitchScene(GameScene::Menu));
+        }
+
+        self.back_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Menu)));
+
+        self.roll.object.sync()?;
+        self.title.sync();
+        self.backdrop.sync()?;
+        Ok(message)
+    }
+}
+
+/// The README without the markdown syntax.
+fn credits_text() -> String {
+    include_str!("../../README.md")
+        .lines()
+        .map(|line| {
+            line.trim_start_matches(['#', '-', ' '])
+                .trim_matches('*')
+                .trim()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
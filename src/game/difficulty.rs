@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Presets scaling how punishing a run is.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    /// The localization key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty.easy",
+            Difficulty::Normal => "difficulty.normal",
+            Difficulty::Hard => "difficulty.hard",
+            Difficulty::Nightmare => "difficulty.nightmare",
+        }
+    }
+
+    /// The next harder preset wrapping around to the easiest.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Maximum health of the paddle.
+    pub fn max_health(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 5.0,
+            Difficulty::Normal => 3.0,
+            Difficulty::Hard => 2.0,
+            Difficulty::Nightmare => 1.0,
+        }
+    }
+
+    /// Health the paddle regenerates per second.
+    pub fn health_regen(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.06,
+            Difficulty::Normal => 0.03,
+            Difficulty::Hard => 0.015,
+            Difficulty::Nightmare => 0.0,
+        }
+    }
+
+    /// Time the paddle can not take damage after getting hit.
+    pub fn grace_period(&self) -> Duration {
+        match self {
+            Difficulty::Easy => Duration::from_millis(1500),
+            Difficulty::Normal => Duration::from_secs(1),
+            Difficulty::Hard => Duration::from_millis(750),
+            Difficulty::Nightmare => Duration::from_millis(500),
+        }
+    }
+
+    /// Scales the time enemies wait between shots.
+    pub fn fire_interval(&self, interval: Duration) -> Duration {
+        interval.mul_f32(match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+            Difficulty::Nightmare => 0.5,
+        })
+    }
+
+    /// Multiplier of the speed of enemy projectiles.
+    pub fn projectile_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Nightmare => 1.5,
+        }
+    }
+
+    /// Scales the amount of enemies a level allows at once.
+    pub fn enemy_limit(&self, limit: u32) -> u32 {
+        match self {
+            Difficulty::Easy => (limit * 3 / 4).max(1),
+            Difficulty::Normal => limit,
+            Difficulty::Hard => limit * 3 / 2,
+            Difficulty::Nightmare => limit * 2,
+        }
+    }
+}
@@ -0,0 +1,911 @@
+//! Ingame editor for the events of stages.
+use std::time::Duration;
+
+use let_engine::{
+    egui::{self, Color32, ComboBox, DragValue, RichText, ScrollArea, Sense, Stroke, TextEdit, Ui},
+    prelude::*,
+};
+
+use super::{
+    difficulty::Difficulty,
+    game_loop::{Condition, LevelMessage, Run, StartAt},
+    locale,
+    stages::{Stage, STAGES},
+    Message,
+};
+use crate::objects::{
+    dialogue::{Choice, DialogueLine, ScreenEffect},
+    enemies::{
+        movement::{Formation, Path},
+        spawn::{DropTable, FirePattern, Spawn},
+        EnemyType,
+    },
+    powerups::PowerUpKind,
+    titles::{TitleCard, TitleMode, TitlePosition, SUBTITLE_SCALE},
+};
+
+/// Size of the playfield a 16:9 window shows, starting at x = 0 and y = -1.
+const PLAYFIELD: Vec2 = vec2(32.0 / 9.0, 2.0);
+/// Width of the playfield preview in points.
+const PLAYFIELD_WIDTH: f32 = 320.0;
+
+pub struct StageEditor {
+    active: bool,
+    /// Index of the edited stage in `STAGES`.
+    stage_index: u32,
+    stage: Stage,
+    selected: Option<usize>,
+    /// The member of the selected event the next click on the playfield places.
+    placing: Option<usize>,
+    status: String,
+}
+
+impl StageEditor {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            stage_index: 0,
+            stage: Stage {
+                name: STAGES[0].to_string(),
+                enemy_limit: 2,
+                event_duration: Duration::from_secs(2),
+                events: vec![],
+            },
+            selected: None,
+            placing: None,
+            status: String::new(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        if self.active {
+            self.load();
+        }
+    }
+
+    pub fn update(&mut self, context: &egui::Context) -> Option<Message> {
+        let mut message = None;
+        let mut open = self.active;
+        egui::Window::new("Stage editor")
+            .open(&mut open)
+            .default_width(720.0)
+            .show(context, |ui| message = self.ui(ui));
+        self.active = open;
+        message
+    }
+
+    fn load(&mut self) {
+        match Stage::load(self.stage_index) {
+            Ok(Some(stage)) => {
+                self.status = format!("Loaded {}.", stage.name);
+                self.stage = stage;
+                self.selected = None;
+                self.placing = None;
+            }
+            Ok(None) => self.status = format!("Stage {} does not exist.", self.stage_index),
+            Err(error) => self.status = format!("Failed to load stage: {error}"),
+        }
+    }
+
+    /// Plays the stage from the given event unless it has mistakes.
+    fn playtest(&mut self, start: StartAt) -> Option<Message> {
+        if let Err(error) = self.stage.validate() {
+            self.status = format!("Can not playtest: {error}");
+            return None;
+        }
+        Some(Message::Play(Run::Playtest(self.stage.clone(), start)))
+    }
+
+    fn ui(&mut self, ui: &mut Ui) -> Option<Message> {
+        let mut message = None;
+        ui.horizontal(|ui| {
+            let stage_index = self.stage_index;
+            ComboBox::from_id_source("stage")
+                .selected_text(STAGES[self.stage_index as usize])
+                .show_ui(ui, |ui| {
+                    for (index, name) in STAGES.iter().enumerate() {
+                        ui.selectable_value(&mut self.stage_index, index as u32, *name);
+                    }
+                });
+            if stage_index != self.stage_index || ui.button("Reload").clicked() {
+                self.load();
+            }
+            if ui.button("Save").clicked() {
+                self.status = match self.stage.save() {
+                    Ok(path) => format!("Saved to {path:?}."),
+                    Err(error) => format!("Failed to save stage: {error}"),
+                };
+            }
+            if ui.button("Reset to built-in").clicked() {
+                match self.stage.reset() {
+                    Ok(()) => self.load(),
+                    Err(error) => self.status = format!("Failed to reset stage: {error}"),
+                }
+            }
+            if ui.button("Playtest").clicked() {
+                message = self.playtest(StartAt::Index(0));
+            }
+        });
+        ui.label(&self.status);
+        ui.horizontal(|ui| {
+            ui.label("Enemy limit");
+            ui.add(DragValue::new(&mut self.stage.enemy_limit).clamp_range(1..=50));
+            ui.label("Time between events");
+            seconds(ui, &mut self.stage.event_duration);
+        });
+        ui.separator();
+
+        ui.columns(2, |columns| {
+            if let Some(playtest) = self.event_list(&mut columns[0]) {
+                message = Some(playtest);
+            }
+            self.event_details(&mut columns[1]);
+        });
+        message
+    }
+
+    /// The list of all events with buttons to rearrange them.
+    fn event_list(&mut self, ui: &mut Ui) -> Option<Message> {
+        let mut message = None;
+        ui.horizontal_wrapped(|ui| {
+            ComboBox::from_id_source("insert")
+                .selected_text("Insert")
+                .show_ui(ui, |ui| {
+                    for (name, template) in templates() {
+                        if ui.selectable_label(false, name).clicked() {
+                            let index = self
+                                .selected
+                                .map_or(self.stage.events.len(), |index| index + 1);
+                            self.stage.events.insert(index, template);
+                            self.selected = Some(index);
+                            self.placing = None;
+                        }
+                    }
+                });
+            let Some(selected) = self
+                .selected
+                .filter(|selected| *selected < self.stage.events.len())
+            else {
+                return;
+            };
+            if ui.button("Up").clicked() && selected > 0 {
+                self.stage.events.swap(selected, selected - 1);
+                self.selected = Some(selected - 1);
+            }
+            if ui.button("Down").clicked() && selected + 1 < self.stage.events.len() {
+                self.stage.events.swap(selected, selected + 1);
+                self.selected = Some(selected + 1);
+            }
+            if ui.button("Duplicate").clicked() {
+                self.stage
+                    .events
+                    .insert(selected + 1, self.stage.events[selected].clone());
+                self.selected = Some(selected + 1);
+            }
+            if ui.button("Delete").clicked() {
+                self.stage.events.remove(selected);
+                self.selected = selected.min(self.stage.events.len()).checked_sub(1);
+                self.placing = None;
+            }
+            if ui.button("Playtest from here").clicked() {
+                message = self.playtest(StartAt::Index(selected));
+            }
+        });
+
+        ScrollArea::vertical()
+            .id_source("events")
+            .max_height(420.0)
+            .show(ui, |ui| {
+                for (index, event) in self.stage.events.iter().enumerate() {
+                    let text = RichText::new(format!("{index:>3} {}", describe(event))).monospace();
+                    if ui
+                        .selectable_label(self.selected == Some(index), text)
+                        .clicked()
+                    {
+                        self.selected = Some(index);
+                        self.placing = None;
+                    }
+                }
+            });
+        message
+    }
+
+    /// Fields of the selected event and the playfield to place spawns on.
+    fn event_details(&mut self, ui: &mut Ui) {
+        let Some(index) = self
+            .selected
+            .filter(|index| *index < self.stage.events.len())
+        else {
+            ui.label("Select an event to edit it.");
+            return;
+        };
+        edit_event(ui, &mut self.stage.events[index], &mut self.placing);
+        ui.separator();
+
+        if self.placing.is_some() {
+            ui.label("Click on the playfield to place the enemy.");
+        }
+        if let Some(position) = self.playfield(ui, index) {
+            if let Some(member) = self.placing.take() {
+                place(&mut self.stage.events[index], member, position);
+            }
+        }
+    }
+
+    /// Draws all spawn positions of the stage, returning where it got clicked.
+    fn playfield(&self, ui: &mut Ui, selected: usize) -> Option<Vec2> {
+        let size = egui::vec2(PLAYFIELD_WIDTH, PLAYFIELD_WIDTH * PLAYFIELD.y / PLAYFIELD.x);
+        let (response, painter) = ui.allocate_painter(size, Sense::click());
+        let rect = response.rect;
+        painter.rect_filled(rect, 2.0, Color32::from_gray(20));
+
+        let to_screen = |position: Vec2| {
+            rect.min
+                + egui::vec2(
+                    position.x / PLAYFIELD.x * rect.width(),
+                    (position.y + 1.0) / PLAYFIELD.y * rect.height(),
+                )
+        };
+        // The paddle
+        painter.circle_filled(to_screen(vec2(0.07, 0.0)), 3.0, Color32::GRAY);
+
+        for (index, event) in self.stage.events.iter().enumerate() {
+            let color = if index == selected {
+                Color32::from_rgb(255, 140, 40)
+            } else {
+                Color32::from_gray(90)
+            };
+            for position in positions(event) {
+                painter.circle_stroke(to_screen(position), 4.0, Stroke::new(1.5, color));
+            }
+        }
+
+        let pointer = response
+            .interact_pointer_pos()
+            .filter(|_| response.clicked())?;
+        let offset = (pointer - rect.min) / rect.size();
+        Some(vec2(offset.x * PLAYFIELD.x, offset.y * PLAYFIELD.y - 1.0))
+    }
+}
+
+/// Events that can be inserted with reasonable defaults.
+fn templates() -> Vec<(&'static str, LevelMessage)> {
+    vec![
+        (
+            "Title",
+            LevelMessage::ShowTitle(TitleCard::new(
+                "Title",
+                Color::from_rgb(0.9, 0.3, 0.1),
+                vec2(60.0, 60.0),
+            )),
+        ),
+        (
+            "Spawn enemy",
+            LevelMessage::Spawn(Spawn::new(EnemyType::Target)),
+        ),
+        (
+            "Spawn group",
+            LevelMessage::SpawnGroup(vec![
+                Spawn::new(EnemyType::Target),
+                Spawn::new(EnemyType::Target),
+            ]),
+        ),
+        (
+            "Spawn formation",
+            LevelMessage::SpawnFormation {
+                enemy: EnemyType::Target,
+                formation: "column".to_string(),
+                count: 3,
+                anchor: vec2(2.4, 0.0),
+                entry: "enter_right".to_string(),
+                idle: "bob".to_string(),
+            },
+        ),
+        (
+            "Time between events",
+            LevelMessage::ChangeWaitingTime(Duration::from_secs(2)),
+        ),
+        ("Enemy limit", LevelMessage::ChangeEnemyLimit(2)),
+        ("Label", LevelMessage::Label("label".to_string())),
+        ("Jump", LevelMessage::Jump("label".to_string())),
+        (
+            "Jump if",
+            LevelMessage::JumpIf {
+                condition: Condition::EnemiesDead,
+                label: "label".to_string(),
+            },
+        ),
+        (
+            "Wait until",
+            LevelMessage::WaitUntil(Condition::EnemiesDead),
+        ),
+        (
+            "Repeat",
+            LevelMessage::Repeat {
+                label: "label".to_string(),
+                times: 1,
+            },
+        ),
+        (
+            "Dialogue",
+            LevelMessage::Dialogue {
+                lines: vec![DialogueLine::new("Speaker", "Text")],
+                pause_fire: true,
+            },
+        ),
+    ]
+}
+
+/// A short summary of the event for the event list.
+fn describe(event: &LevelMessage) -> String {
+    match event {
+        LevelMessage::None => "Nothing".to_string(),
+        LevelMessage::Done => "Done".to_string(),
+        LevelMessage::SpawnEnemy(enemy) => format!("Spawn {enemy:?}"),
+        LevelMessage::Spawn(spawn) => match spawn.position {
+            Some(position) => format!(
+                "Spawn {:?} at {:.2}, {:.2}",
+                spawn.enemy, position.x, position.y
+            ),
+            None => format!("Spawn {:?}", spawn.enemy),
+        },
+        LevelMessage::SpawnGroup(spawns) => format!("Spawn group of {}", spawns.len()),
+        LevelMessage::SpawnFormation {
+            enemy,
+            formation,
+            count,
+            ..
+        } => format!("Spawn {count} {enemy:?} in a {formation}"),
+        LevelMessage::ChangeWaitingTime(duration) => {
+            format!("Wait {:.1}s between events", duration.as_secs_f32())
+        }
+        LevelMessage::ChangeEnemyLimit(limit) => format!("Enemy limit {limit}"),
+        LevelMessage::ShowTitle(card) => {
+            let mut text = card.text.clone();
+            if text.chars().count() > 32 {
+                text = text.chars().take(32).chain("...".chars()).collect();
+            }
+            format!("Title \"{text}\"")
+        }
+        LevelMessage::Label(label) => format!("{label}:"),
+        LevelMessage::Jump(label) => format!("Jump to {label}"),
+        LevelMessage::JumpIf { condition, label } => {
+            format!("If {} jump to {label}", describe_condition(condition))
+        }
+        LevelMessage::WaitUntil(condition) => {
+            format!("Wait until {}", describe_condition(condition))
+        }
+        LevelMessage::Repeat { label, times } => format!("Repeat from {label} {times} times"),
+        LevelMessage::Dialogue { lines, .. } => match lines.first() {
+            Some(line) => format!("Dialogue: {}: {}", line.speaker, line.text),
+            None => "Empty dialogue".to_string(),
+        },
+    }
+}
+
+fn describe_condition(condition: &Condition) -> String {
+    match condition {
+        Condition::EnemiesDead => "all enemies are dead".to_string(),
+        Condition::HealthBelow(health) => format!("health is below {health}"),
+        Condition::ScoreReached(score) => format!("score reached {score}"),
+        Condition::DifficultyAtLeast(difficulty) => {
+            format!("difficulty is at least {}", difficulty.name())
+        }
+        Condition::Flag(flag) => format!("{flag} is set"),
+        Condition::Not(condition) => format!("not {}", describe_condition(condition)),
+    }
+}
+
+/// The spawn positions the event places enemies at.
+fn positions(event: &LevelMessage) -> Vec<Vec2> {
+    match event {
+        LevelMessage::Spawn(spawn) => spawn.position.into_iter().collect(),
+        LevelMessage::SpawnGroup(spawns) => {
+            spawns.iter().filter_map(|spawn| spawn.position).collect()
+        }
+        LevelMessage::SpawnFormation {
+            formation,
+            count,
+            anchor,
+            ..
+        } => Formation::named(formation, *count)
+            .map(|formation| formation.anchors(*anchor).collect())
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+/// Sets the spawn position of a member of the event.
+fn place(event: &mut LevelMessage, member: usize, position: Vec2) {
+    match event {
+        LevelMessage::SpawnEnemy(enemy) => {
+            *event = LevelMessage::Spawn(Spawn::new(*enemy).position(position))
+        }
+        LevelMessage::Spawn(spawn) => spawn.position = Some(position),
+        LevelMessage::SpawnGroup(spawns) => {
+            if let Some(spawn) = spawns.get_mut(member) {
+                spawn.position = Some(position);
+            }
+        }
+        LevelMessage::SpawnFormation { anchor, .. } => *anchor = position,
+        _ => (),
+    }
+}
+
+fn edit_event(ui: &mut Ui, event: &mut LevelMessage, placing: &mut Option<usize>) {
+    match event {
+        LevelMessage::None | LevelMessage::Done => {
+            ui.label("This event does nothing.");
+        }
+        LevelMessage::SpawnEnemy(enemy) => {
+            enemy_combo(ui, enemy);
+            if ui.button("Customize").clicked() {
+                *event = LevelMessage::Spawn(Spawn::new(*enemy));
+            }
+        }
+        LevelMessage::Spawn(spawn) => edit_spawn(ui, spawn, 0, placing),
+        LevelMessage::SpawnGroup(spawns) => {
+            let mut remove = None;
+            for (member, spawn) in spawns.iter_mut().enumerate() {
+                egui::CollapsingHeader::new(format!("Enemy {member}"))
+                    .id_source(member)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.push_id(member, |ui| edit_spawn(ui, spawn, member, placing));
+                        if ui.button("Remove").clicked() {
+                            remove = Some(member);
+                        }
+                    });
+            }
+            if let Some(member) = remove {
+                spawns.remove(member);
+                *placing = None;
+            }
+            if ui.button("Add enemy").clicked() {
+                spawns.push(Spawn::new(EnemyType::Target));
+            }
+        }
+        LevelMessage::SpawnFormation {
+            enemy,
+            formation,
+            count,
+            anchor,
+            entry,
+            idle,
+        } => {
+            enemy_combo(ui, enemy);
+            name_combo(ui, "Formation", formation, &Formation::NAMES);
+            ui.horizontal(|ui| {
+                ui.label("Count");
+                ui.add(DragValue::new(count).clamp_range(1..=12));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Anchor");
+                position(ui, anchor);
+                if ui.button("Place").clicked() {
+                    *placing = Some(0);
+                }
+            });
+            name_combo(ui, "Entry", entry, &Path::NAMES);
+            name_combo(ui, "Idle", idle, &Path::NAMES);
+        }
+        LevelMessage::ChangeWaitingTime(duration) => {
+            ui.horizontal(|ui| {
+                ui.label("Time between events");
+                seconds(ui, duration);
+            });
+        }
+        LevelMessage::ChangeEnemyLimit(limit) => {
+            ui.horizontal(|ui| {
+                ui.label("Enemy limit");
+                ui.add(DragValue::new(limit).clamp_range(1..=50));
+            });
+        }
+        LevelMessage::ShowTitle(card) => {
+            ui.horizontal(|ui| {
+                ui.label("Color");
+                let color = &mut card.color;
+                let mut rgba = [color.r(), color.g(), color.b(), color.a()];
+                if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+                    *color = Color::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+                }
+                ui.label("Size");
+                position(ui, &mut card.size);
+            });
+            ui.add(TextEdit::multiline(&mut card.text).desired_width(f32::INFINITY));
+            ui.horizontal(|ui| {
+                let mut custom = card.subtitle.is_some();
+                if ui.checkbox(&mut custom, "Subtitle").changed() {
+                    card.subtitle = custom.then(String::new);
+                }
+                if let Some(subtitle) = card.subtitle.as_mut() {
+                    ui.text_edit_singleline(subtitle);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Position");
+                ComboBox::from_id_source("title position")
+                    .selected_text(card.position.name())
+                    .show_ui(ui, |ui| {
+                        for option in TitlePosition::ALL {
+                            ui.selectable_value(&mut card.position, option, option.name());
+                        }
+                    });
+                ui.label("Mode");
+                ComboBox::from_id_source("title mode")
+                    .selected_text(card.mode.name())
+                    .show_ui(ui, |ui| {
+                        for option in TitleMode::ALL {
+                            ui.selectable_value(&mut card.mode, option, option.name());
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Held for");
+                ui.add(
+                    DragValue::new(&mut card.duration)
+                        .speed(0.1)
+                        .clamp_range(0.0..=30.0)
+                        .suffix("s"),
+                );
+            });
+            ui.label("Preview");
+            egui::Frame::none()
+                .fill(Color32::BLACK)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(locale::text(&card.text))
+                            .color(color32(card.color))
+                            .size(card.size.y * 0.3),
+                    );
+                    if let Some(subtitle) = &card.subtitle {
+                        ui.label(
+                            RichText::new(locale::text(subtitle))
+                                .color(Color32::WHITE)
+                                .size(card.size.y * 0.3 * SUBTITLE_SCALE),
+                        );
+                    }
+                });
+        }
+        LevelMessage::Label(label) | LevelMessage::Jump(label) => {
+            ui.horizontal(|ui| {
+                ui.label("Label");
+                ui.text_edit_singleline(label);
+            });
+        }
+        LevelMessage::JumpIf { condition, label } => {
+            edit_condition(ui, condition);
+            ui.horizontal(|ui| {
+                ui.label("Jump to");
+                ui.text_edit_singleline(label);
+            });
+        }
+        LevelMessage::WaitUntil(condition) => edit_condition(ui, condition),
+        LevelMessage::Repeat { label, times } => {
+            ui.horizontal(|ui| {
+                ui.label("Jump back to");
+                ui.text_edit_singleline(label);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Times");
+                ui.add(DragValue::new(times).clamp_range(0..=100));
+            });
+        }
+        LevelMessage::Dialogue { lines, pause_fire } => {
+            ui.checkbox(pause_fire, "Enemies hold their fire");
+            let mut remove = None;
+            for (index, line) in lines.iter_mut().enumerate() {
+                egui::CollapsingHeader::new(format!("Line {index}"))
+                    .id_source(index)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ui.push_id(index, |ui| edit_line(ui, line));
+                        if ui.button("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                    });
+            }
+            if let Some(index) = remove {
+                lines.remove(index);
+            }
+            if ui.button("Add line").clicked() {
+                let speaker = lines.last().map_or("Speaker", |line| line.speaker.as_str());
+                lines.push(DialogueLine::new(speaker, "Text"));
+            }
+        }
+    }
+}
+
+fn edit_line(ui: &mut Ui, line: &mut DialogueLine) {
+    ui.horizontal(|ui| {
+        ui.label("Speaker");
+        ui.text_edit_singleline(&mut line.speaker);
+    });
+    ui.horizontal(|ui| {
+        let mut custom = line.portrait.is_some();
+        if ui.checkbox(&mut custom, "Portrait").changed() {
+            line.portrait = custom.then(String::new);
+        }
+        if let Some(portrait) = line.portrait.as_mut() {
+            ui.text_edit_singleline(portrait);
+        }
+    });
+    ui.add(TextEdit::multiline(&mut line.text).desired_width(f32::INFINITY));
+    ui.horizontal(|ui| {
+        ui.label("Effect");
+        let name = |effect: &Option<ScreenEffect>| match effect {
+            None => "none",
+            Some(ScreenEffect::Shake) => "shake",
+            Some(ScreenEffect::Flash) => "flash",
+            Some(ScreenEffect::Darken) => "darken",
+        };
+        ComboBox::from_id_source("effect")
+            .selected_text(name(&line.effect))
+            .show_ui(ui, |ui| {
+                for effect in [
+                    None,
+                    Some(ScreenEffect::Shake),
+                    Some(ScreenEffect::Flash),
+                    Some(ScreenEffect::Darken),
+                ] {
+                    ui.selectable_value(&mut line.effect, effect, name(&effect));
+                }
+            });
+    });
+    let mut remove = None;
+    for (index, choice) in line.choices.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("[{}]", index + 1));
+            ui.text_edit_singleline(&mut choice.text);
+            ui.label("sets");
+            ui.text_edit_singleline(&mut choice.flag);
+            if ui.button("Remove").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        line.choices.remove(index);
+    }
+    // Only the number keys 1 to 4 can pick a choice.
+    if line.choices.len() < 4 && ui.button("Add choice").clicked() {
+        line.choices.push(Choice {
+            text: "Choice".to_string(),
+            flag: "flag".to_string(),
+        });
+    }
+}
+
+fn edit_spawn(ui: &mut Ui, spawn: &mut Spawn, member: usize, placing: &mut Option<usize>) {
+    enemy_combo(ui, &mut spawn.enemy);
+
+    ui.horizontal(|ui| {
+        let mut custom = spawn.position.is_some();
+        if ui.checkbox(&mut custom, "Position").changed() {
+            spawn.position = custom.then_some(vec2(2.4, 0.0));
+        }
+        if let Some(anchor) = spawn.position.as_mut() {
+            position(ui, anchor);
+        }
+        if ui.button("Place").clicked() {
+            *placing = Some(member);
+        }
+    });
+    optional_name_combo(ui, "Entry", &mut spawn.entry, &Path::NAMES);
+    optional_name_combo(ui, "Idle", &mut spawn.idle, &Path::NAMES);
+
+    ui.horizontal(|ui| {
+        let mut custom = spawn.hp.is_some();
+        if ui.checkbox(&mut custom, "HP").changed() {
+            spawn.hp = custom.then_some(2.0);
+        }
+        if let Some(hp) = spawn.hp.as_mut() {
+            ui.add(DragValue::new(hp).speed(0.1).clamp_range(0.1..=100.0));
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Firing");
+        let name = |pattern: &Option<FirePattern>| match pattern {
+            None => "default",
+            Some(FirePattern::Random) => "random",
+            Some(FirePattern::Straight) => "straight",
+            Some(FirePattern::Burst { .. }) => "burst",
+        };
+        ComboBox::from_id_source("pattern")
+            .selected_text(name(&spawn.pattern))
+            .show_ui(ui, |ui| {
+                for pattern in [
+                    None,
+                    Some(FirePattern::Random),
+                    Some(FirePattern::Straight),
+                    Some(FirePattern::Burst {
+                        count: 3,
+                        spread: 0.6,
+                    }),
+                ] {
+                    let selected = name(&spawn.pattern) == name(&pattern);
+                    if ui.selectable_label(selected, name(&pattern)).clicked() {
+                        spawn.pattern = pattern;
+                    }
+                }
+            });
+        if let Some(FirePattern::Burst { count, spread }) = spawn.pattern.as_mut() {
+            ui.add(DragValue::new(count).clamp_range(1..=12).prefix("shots "));
+            ui.add(
+                DragValue::new(spread)
+                    .speed(0.01)
+                    .clamp_range(0.0..=3.0)
+                    .prefix("spread "),
+            );
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let mut custom = spawn.drops.is_some();
+        if ui.checkbox(&mut custom, "Drops").changed() {
+            spawn.drops = custom.then(DropTable::default);
+        }
+        if let Some(drops) = spawn.drops.as_mut() {
+            ui.add(
+                DragValue::new(&mut drops.chance)
+                    .speed(0.01)
+                    .clamp_range(0.0..=1.0)
+                    .prefix("chance "),
+            );
+            if ui.button("None").clicked() {
+                *drops = DropTable::none();
+            }
+        }
+    });
+    if let Some(drops) = spawn.drops.as_mut() {
+        ui.horizontal_wrapped(|ui| {
+            for kind in PowerUpKind::ALL {
+                let mut weight = drops
+                    .kinds
+                    .iter()
+                    .find(|(dropped, _)| *dropped == kind)
+                    .map_or(0, |(_, weight)| *weight);
+                ui.label(kind.name());
+                if ui
+                    .add(DragValue::new(&mut weight).clamp_range(0..=10))
+                    .changed()
+                {
+                    drops.kinds.retain(|(dropped, _)| *dropped != kind);
+                    if weight > 0 {
+                        drops.kinds.push((kind, weight));
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn edit_condition(ui: &mut Ui, condition: &mut Condition) {
+    let name = |condition: &Condition| match condition {
+        Condition::EnemiesDead => "enemies dead",
+        Condition::HealthBelow(_) => "health below",
+        Condition::ScoreReached(_) => "score reached",
+        Condition::DifficultyAtLeast(_) => "difficulty at least",
+        Condition::Flag(_) => "flag set",
+        Condition::Not(_) => "not",
+    };
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source("condition")
+            .selected_text(name(condition))
+            .show_ui(ui, |ui| {
+                for template in [
+                    Condition::EnemiesDead,
+                    Condition::HealthBelow(2.0),
+                    Condition::ScoreReached(1000),
+                    Condition::DifficultyAtLeast(Difficulty::Hard),
+                    Condition::Flag("flag".to_string()),
+                    Condition::Not(Box::new(Condition::EnemiesDead)),
+                ] {
+                    let selected = name(condition) == name(&template);
+                    if ui.selectable_label(selected, name(&template)).clicked() && !selected {
+                        *condition = template;
+                    }
+                }
+            });
+        match condition {
+            Condition::HealthBelow(health) => {
+                ui.add(DragValue::new(health).speed(0.1).clamp_range(0.0..=10.0));
+            }
+            Condition::ScoreReached(score) => {
+                ui.add(DragValue::new(score).speed(10.0));
+            }
+            Condition::DifficultyAtLeast(difficulty) => {
+                ComboBox::from_id_source("difficulty")
+                    .selected_text(difficulty.name())
+                    .show_ui(ui, |ui| {
+                        for option in Difficulty::ALL {
+                            ui.selectable_value(difficulty, option, option.name());
+                        }
+                    });
+            }
+            Condition::Flag(flag) => {
+                ui.text_edit_singleline(flag);
+            }
+            Condition::EnemiesDead | Condition::Not(_) => (),
+        }
+    });
+    if let Condition::Not(condition) = condition {
+        ui.push_id("not", |ui| {
+            ui.indent("not", |ui| edit_condition(ui, condition))
+        });
+    }
+}
+
+fn enemy_combo(ui: &mut Ui, enemy: &mut EnemyType) {
+    ui.horizontal(|ui| {
+        ui.label("Enemy");
+        ComboBox::from_id_source("enemy")
+            .selected_text(format!("{enemy:?}"))
+            .show_ui(ui, |ui| {
+                for option in EnemyType::SPAWNABLE {
+                    ui.selectable_value(enemy, option, format!("{option:?}"));
+                }
+            });
+    });
+}
+
+fn name_combo(ui: &mut Ui, label: &str, value: &mut String, names: &[&str]) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ComboBox::from_id_source(label)
+            .selected_text(value.as_str())
+            .show_ui(ui, |ui| {
+                for name in names {
+                    ui.selectable_value(value, name.to_string(), *name);
+                }
+            });
+    });
+}
+
+/// Like `name_combo`, but can be left at the default of the enemy.
+fn optional_name_combo(ui: &mut Ui, label: &str, value: &mut Option<String>, names: &[&str]) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ComboBox::from_id_source(label)
+            .selected_text(value.as_deref().unwrap_or("default"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(value, None, "default");
+                for name in names {
+                    ui.selectable_value(value, Some(name.to_string()), *name);
+                }
+            });
+    });
+}
+
+fn position(ui: &mut Ui, position: &mut Vec2) {
+    ui.add(DragValue::new(&mut position.x).speed(0.01).prefix("x "));
+    ui.add(DragValue::new(&mut position.y).speed(0.01).prefix("y "));
+}
+
+fn seconds(ui: &mut Ui, duration: &mut Duration) {
+    let mut seconds = duration.as_secs_f32();
+    if ui
+        .add(
+            DragValue::new(&mut seconds)
+                .speed(0.1)
+                .clamp_range(0.0..=60.0)
+                .suffix("s"),
+        )
+        .changed()
+    {
+        *duration = Duration::from_secs_f32(seconds);
+    }
+}
+
+fn color32(color: Color) -> Color32 {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0) as u8;
+    Color32::from_rgba_unmultiplied(
+        channel(color.r()),
+        channel(color.g()),
+        channel(color.b()),
+        channel(color.a()),
+    )
+}
@@ -1,31 +1,124 @@
 use let_engine::prelude::*;
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, HashSet},
     path::PathBuf,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use crate::{
     objects::{
-        enemies::{Enemy, EnemyMessage, EnemyType},
+        abilities::{Abilities, PARRY_BONUS, PARRY_RANGE},
+        clock::{self, GameInstant},
+        debug::DebugOverlay,
+        dialogue::{Dialogue, DialogueLine},
+        enemies::{
+            movement::Formation, spawn::Spawn, Enemy, EnemyMessage, EnemyType, Hit, HitOutcome,
+            Kill,
+        },
         paddle::Paddle,
+        particles::ParticleSystem,
+        powerups::{Pickup, PowerUpKind, PowerUps},
         projectiles::Projectile,
         settings::GameMenu,
+        titles::{TitleCard, Titles},
+        trajectory::{self, Trajectory},
         Camera,
     },
-    FONT_STINGRAY, HEIGHT,
+    HEIGHT,
 };
 
-use super::{load_material, sounds::Sounds, stages::part_one::tutorial, Layers, Message, SAMPLER};
+use super::{
+    accessibility,
+    difficulty::Difficulty,
+    input::Action,
+    layout::{self, Anchor},
+    load_material, locale,
+    perf::{FrameReport, Section, SectionTimer},
+    sounds::Sounds,
+    stages::{self, endless::WaveGenerator, Stage},
+    GameSettings, Layers, Message, SAMPLER,
+};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Clone, Copy, Debug, Deserialize, Serialize)]
+/// Angle in radians the extra shots of a split rebound spread out with.
+const SPLIT_ANGLE: f32 = 0.25;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct GameState {
+    /// The next stage that is not completed yet.
     pub stage: u32,
     pub score: u32,
     pub kills: u32,
+    /// The difficulty new runs start with.
+    pub difficulty: Difficulty,
+    /// The best score of every completed stage for each difficulty.
+    pub best_scores: HashMap<Difficulty, Vec<u32>>,
+    /// Seconds spent playing without pauses.
+    pub play_time: f64,
+    pub hard_shots: u32,
+    /// The best endless run of each difficulty.
+    pub endless_records: HashMap<Difficulty, EndlessRecord>,
+    /// Flags set by the choices made in dialogues.
+    pub flags: HashSet<String>,
+}
+
+/// The layout of the binary save before the game state grew past these fields.
+#[derive(Deserialize)]
+struct LegacyState {
+    stage: u32,
+    score: u32,
+    kills: u32,
+}
+
+/// The furthest an endless run got.
+#[derive(Default, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct EndlessRecord {
+    pub score: u32,
+    pub wave: u32,
+    pub seed: u64,
+}
+
+/// What a run plays through.
+#[derive(Clone, Debug)]
+pub enum Run {
+    /// The scripted stages starting at the given one.
+    Stage(u32),
+    /// Endless waves generated from the given seed.
+    Endless(u64),
+    /// Tries out a stage starting at the given event without saving any progress.
+    Playtest(Stage, StartAt),
+}
+
+/// The event a level starts at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StartAt {
+    Index(usize),
+    /// The event after the label.
+    Label(String),
+}
+
+impl StartAt {
+    /// The index of the first event.
+    pub fn resolve(&self, events: &[LevelMessage]) -> Result<usize> {
+        match self {
+            StartAt::Index(index) if *index <= events.len() => Ok(*index),
+            StartAt::Index(index) => Err(anyhow!(
+                "Event {index} is out of range. The stage only has {} events.",
+                events.len()
+            )),
+            // The console lowercases everything, so labels are matched ignoring case.
+            StartAt::Label(name) => events
+                .iter()
+                .position(|message| {
+                    matches!(message, LevelMessage::Label(label) if label.eq_ignore_ascii_case(name))
+                })
+                .map(|index| index + 1)
+                .ok_or_else(|| anyhow!("Unknown label {name}")),
+        }
+    }
 }
 
 impl GameState {
@@ -46,13 +139,29 @@ impl GameState {
         use std::fs;
         let data_dir = Self::data_dir()?;
 
-        let file_path = data_dir.join("state.sav");
-        if file_path.exists() {
-            let file = fs::read(&file_path)?;
+        let file_path = data_dir.join("state.ron");
+        let legacy_path = data_dir.join("state.sav");
+        let legacy = !file_path.exists();
+        let (path, data) = if !legacy {
+            let data = Self::from_ron(&fs::read_to_string(&file_path)?);
+            (file_path, data)
+        } else if legacy_path.exists() {
+            let data = Self::from_legacy(&fs::read(&legacy_path)?);
+            (legacy_path, data)
+        } else {
+            return Ok(Self::default());
+        };
 
-            let data = bincode::deserialize(&file);
-            if let Err(error) = data {
-                let new = file_path.with_extension("old");
+        match data {
+            Ok(state) => {
+                // Migrates the legacy save once, leaving the old file untouched.
+                if legacy {
+                    state.save()?;
+                }
+                Ok(state)
+            }
+            Err(error) => {
+                let new = path.with_extension("old");
                 let error =
                     anyhow!("Could not deserialize game save. It might be corrupted:\n{error}");
                 if native_dialog::MessageDialog::new()
@@ -61,15 +170,54 @@ impl GameState {
                     .set_type(native_dialog::MessageType::Error)
                     .show_confirm()?
                 {
-                    fs::rename(&file_path, &new)?;
+                    fs::rename(&path, &new)?;
                     return Ok(Self::default());
                 }
-                return Err(error);
+                Err(error)
             }
+        }
+    }
 
-            Ok(data?)
-        } else {
-            Ok(Self::default())
+    /// Reads a save in the current format. Fields missing from older saves keep their defaults.
+    fn from_ron(data: &str) -> Result<Self> {
+        Ok(ron::from_str(data)?)
+    }
+
+    /// Reads a binary `state.sav` from before saves were stored as RON.
+    fn from_legacy(data: &[u8]) -> Result<Self> {
+        let legacy: LegacyState = bincode::deserialize(data)?;
+        Ok(Self {
+            stage: legacy.stage,
+            score: legacy.score,
+            kills: legacy.kills,
+            ..Default::default()
+        })
+    }
+
+    /// The best score of the stage on the current difficulty.
+    pub fn best_score(&self, stage: u32) -> u32 {
+        self.best_scores
+            .get(&self.difficulty)
+            .and_then(|scores| scores.get(stage as usize))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Records the score of a completed stage if it beats the best one on the current difficulty.
+    pub fn submit_score(&mut self, stage: u32, score: u32) {
+        let scores = self.best_scores.entry(self.difficulty).or_default();
+        let stage = stage as usize;
+        if scores.len() <= stage {
+            scores.resize(stage + 1, 0);
+        }
+        scores[stage] = scores[stage].max(score);
+    }
+
+    /// Records an endless run if it scored higher than the best one on the current difficulty.
+    pub fn submit_endless(&mut self, record: EndlessRecord) {
+        let best = self.endless_records.entry(self.difficulty).or_default();
+        if record.score > best.score {
+            *best = record;
         }
     }
 
@@ -77,29 +225,50 @@ impl GameState {
         use std::fs;
         let data_dir = Self::data_dir()?;
 
-        let data = bincode::serialize(&self)?;
+        let data = ron::ser::to_string_pretty(&self, ron::ser::PrettyConfig::new())?;
 
-        Ok(fs::write(data_dir.join("state.sav"), data)?)
+        Ok(fs::write(data_dir.join("state.ron"), data)?)
     }
 }
 
 pub struct Loop {
     pub state: GameState,
+    /// The stage that is currently played.
+    pub stage: u32,
+    /// The seed of the waves if this is an endless run.
+    endless: Option<u64>,
+    /// The stage and first event of a playtest.
+    playtest: Option<(Stage, StartAt)>,
+    /// Score made in the current stage.
+    stage_score: u32,
     sounds: Sounds,
+    /// Whether the sounds are paused along with the game.
+    sounds_paused: bool,
 
     pub paddle: Paddle,
+    abilities: Abilities,
     pub camera: Camera,
     score: Label<Object>,
     layers: Layers,
 
-    title: Title,
+    titles: Titles,
+    dialogue: Dialogue,
     background: Background,
     level: Option<Level>,
 
     menu: GameMenu,
+    trajectory: Option<Trajectory>,
+    /// Seconds of flight the trajectory is previewed for.
+    preview_length: f32,
+    aim_assist: bool,
+    debug: Option<DebugOverlay>,
+    section_times: SectionTimer,
 
     pub enemies: Vec<Box<dyn Enemy>>,
     pub projectiles: Vec<Box<dyn Projectile>>,
+    pub particles: ParticleSystem,
+    pub pickups: Vec<Pickup>,
+    powerups: PowerUps,
 }
 
 impl Loop {
@@ -107,17 +276,18 @@ impl Loop {
         let state = GameState::load_or_init()?;
         let sounds = Sounds::new()?;
         let background = Background::new(&layers.main)?;
-        let title = Title::new(&layers.ui)?;
+        let titles = Titles::new(&layers.ui);
+        let dialogue = Dialogue::new(&layers.ui)?;
         let level = None;
         let score = Label::new(
-            &FONT_STINGRAY,
+            locale::font(),
             LabelCreateInfo::default()
-                .scale(vec2(50.0, 50.0))
+                .scale(accessibility::text_size(vec2(50.0, 50.0)))
                 .text(state.score.to_string())
-                .align(Direction::N)
+                .align(Anchor::Top.align())
                 .appearance(
                     Appearance::default()
-                        .transform(Transform::default().size(vec2(2.0, 0.96)))
+                        .transform(layout::label_transform(Vec2::ZERO))
                         .color(Color::BLACK),
                 ),
         )
@@ -132,25 +302,76 @@ impl Loop {
         menu.set_visible(false);
         menu.set_enabled(false);
 
+        let paddle = Paddle::new(&layers.main, state.difficulty)?;
+        let abilities = Abilities::new(&layers.ui, &paddle)?;
+
         Ok(Self {
+            stage: state.stage,
+            endless: None,
+            playtest: None,
+            stage_score: 0,
             state,
             sounds,
-            paddle: Paddle::new(&layers.main)?,
+            sounds_paused: false,
+            paddle,
+            abilities,
             camera,
             score,
             layers: layers.clone(),
-            title,
+            titles,
+            dialogue,
             background,
             level,
             menu,
+            trajectory: None,
+            preview_length: 0.0,
+            aim_assist: false,
+            debug: None,
+            section_times: SectionTimer::start(),
             enemies: vec![],
             projectiles: vec![],
+            particles: ParticleSystem::new(&layers.main),
+            pickups: vec![],
+            powerups: PowerUps::new(&layers.ui)?,
         })
     }
 
+    pub fn start(&mut self, run: Run) {
+        match run {
+            Run::Stage(stage) => self.stage = stage,
+            Run::Endless(seed) => self.endless = Some(seed),
+            Run::Playtest(stage, from) => self.playtest = Some((stage, from)),
+        }
+    }
+
+    /// Rebuilds the pause menu in the current language, keeping it open if it was.
+    pub fn rebuild_menu(&mut self) -> Result<()> {
+        let open = self.menu.is_enabled();
+        let mut menu = GameMenu::new(&self.layers.ui)?;
+        menu.set_visible(open);
+        menu.set_enabled(open);
+        std::mem::replace(&mut self.menu, menu).remove();
+        Ok(())
+    }
+
     pub fn unload(mut self) {
+        // Leaving through the pause menu would otherwise keep the game paused.
+        TIME.set_scale(1.0);
+        if let (Some(seed), Some(level)) = (self.endless, self.level.as_ref()) {
+            self.state.submit_endless(EndlessRecord {
+                score: self.stage_score,
+                wave: level.wave(),
+                seed,
+            });
+        }
+        // Playtests should not count towards the statistics.
+        if self.playtest.is_none() {
+            let _ = self.state.save();
+        }
         self.background.unload();
-        self.title.remove();
+        self.titles.remove();
+        self.dialogue.remove();
+        self.abilities.remove();
         self.paddle.unload();
         let _ = self.score.object.remove();
         if let Some(level) = self.level {
@@ -162,49 +383,219 @@ impl Loop {
         for projectile in &mut self.projectiles {
             projectile.remove();
         }
+        self.particles.remove();
+        for pickup in self.pickups {
+            pickup.remove();
+        }
+        self.powerups.remove();
         self.menu.remove();
+        if let Some(trajectory) = self.trajectory {
+            trajectory.remove();
+        }
+        if let Some(debug) = self.debug {
+            debug.remove();
+        }
+    }
+
+    /// Shows or hides the collider debug overlay.
+    pub fn show_colliders(&mut self, show: bool) -> Result<()> {
+        match (show, self.debug.take()) {
+            (true, None) => self.debug = Some(DebugOverlay::new(&self.layers)?),
+            (true, debug) => self.debug = debug,
+            (false, Some(debug)) => debug.remove(),
+            (false, None) => (),
+        }
+        Ok(())
+    }
+
+    /// Applies the gameplay related settings.
+    pub fn apply_settings(&mut self, settings: &GameSettings) -> Result<()> {
+        self.preview_length = settings.trajectory_preview;
+        self.aim_assist = settings.aim_assist;
+        match (settings.trajectory_preview > 0.0, self.trajectory.take()) {
+            (true, None) => self.trajectory = Some(Trajectory::new(&self.layers.main)?),
+            (true, trajectory) => self.trajectory = trajectory,
+            (false, Some(trajectory)) => trajectory.remove(),
+            (false, None) => (),
+        }
+        Ok(())
+    }
+
+    /// Releases the cursor when the window loses focus and pauses the game if `pause` is set.
+    ///
+    /// Regaining focus does not resume a paused game, but confines the cursor again if it is still running.
+    pub fn set_focused(&mut self, focused: bool, pause: bool) {
+        if !focused && pause {
+            self.menu.pause();
+        }
+        // The open menu already released the cursor.
+        if self.menu.is_enabled() {
+            return;
+        }
+        if let Some(window) = SETTINGS.window() {
+            window.set_cursor_visible(!focused);
+            let _ = window.set_cursor_grab(if focused {
+                CursorGrabMode::Confined
+            } else {
+                CursorGrabMode::None
+            });
+        }
+    }
+
+    /// Pauses or resumes all sounds of the game when the pause state changes.
+    fn set_sounds_paused(&mut self, paused: bool) {
+        if self.sounds_paused == paused {
+            return;
+        }
+        self.sounds_paused = paused;
+        self.sounds.set_paused(paused);
+        for enemy in &self.enemies {
+            enemy.set_sounds_paused(paused);
+        }
+        for projectile in &self.projectiles {
+            projectile.set_sound_paused(paused);
+        }
+    }
+
+    /// Reports the time spent in each section of the last update and the amount of objects.
+    pub fn frame_report(&self) -> FrameReport {
+        FrameReport {
+            sections: self.section_times.times(),
+            enemies: self.enemies.len(),
+            projectiles: self.projectiles.len(),
+            particles: self.particles.count(),
+        }
     }
 
     pub fn update(&mut self) -> Result<Option<Message>> {
-        self.paddle.health = (self.paddle.health + TIME.delta_time() as f32 * 0.03)
-            .clamp(0.0, self.paddle.max_health);
+        if self.menu.is_enabled() {
+            // Everything stands still while paused, only the menu keeps working.
+            self.set_sounds_paused(true);
+            self.section_times = SectionTimer::start();
+            return self.menu.update();
+        }
+        self.set_sounds_paused(false);
+
+        let mut timer = SectionTimer::start();
+        self.state.play_time += TIME.delta_time();
+        self.paddle.health = (self.paddle.health
+            + TIME.delta_time() as f32 * self.state.difficulty.health_regen())
+        .clamp(0.0, self.paddle.max_health);
+        self.powerups.update();
+        self.paddle.arrow_speed = if self.powerups.is_active(PowerUpKind::FastArrow) {
+            2.0
+        } else {
+            1.0
+        };
+        self.paddle
+            .set_height(if self.powerups.is_active(PowerUpKind::Wide) {
+                1.5
+            } else {
+                1.0
+            })?;
+        self.abilities.update(&mut self.paddle)?;
+        if self.aim_assist {
+            trajectory::assist_aim(&mut self.paddle, &self.enemies);
+        }
         self.paddle.update();
 
         if self.paddle.health <= 0.0 {
             return Ok(Some(Message::SwitchScene(super::GameScene::Menu)));
         }
-
-        if let Some(level) = self.level.as_mut() {
-            let message = level.progress()?;
+        self.update_pickups()?;
+        timer.lap(Section::Paddle);
+
+        if self.dialogue.is_open() {
+            // The level waits for the dialogue to be read.
+        } else if let Some(level) = self.level.as_mut() {
+            let context = LevelContext {
+                health: self.paddle.health,
+                score: self.stage_score,
+                flags: &self.state.flags,
+            };
+            let message = level.progress(&context)?;
 
             match message {
                 LevelMessage::SpawnEnemy(enemy) => {
-                    self.enemies
-                        .push(enemy.spawn(&self.layers.main, &self.sounds)?);
+                    self.enemies.push(Spawn::from(enemy).spawn(
+                        &self.layers.main,
+                        &self.sounds,
+                        self.state.difficulty,
+                    )?);
+                }
+                LevelMessage::Spawn(spawn) => {
+                    self.enemies.push(spawn.spawn(
+                        &self.layers.main,
+                        &self.sounds,
+                        self.state.difficulty,
+                    )?);
+                }
+                LevelMessage::SpawnGroup(spawns) => {
+                    for spawn in spawns {
+                        self.enemies.push(spawn.spawn(
+                            &self.layers.main,
+                            &self.sounds,
+                            self.state.difficulty,
+                        )?);
+                    }
+                }
+                LevelMessage::SpawnFormation {
+                    enemy,
+                    formation,
+                    count,
+                    anchor,
+                    entry,
+                    idle,
+                } => {
+                    let formation = Formation::named(&formation, count)
+                        .ok_or_else(|| anyhow!("Unknown formation {formation}"))?;
+                    for anchor in formation.anchors(anchor) {
+                        let spawn = Spawn::new(enemy)
+                            .position(anchor)
+                            .entry(entry.as_str())
+                            .idle(idle.as_str());
+                        self.enemies.push(spawn.spawn(
+                            &self.layers.main,
+                            &self.sounds,
+                            self.state.difficulty,
+                        )?);
+                    }
                 }
-                LevelMessage::ShowTitle { color, size, text } => {
-                    self.title.set_color(color);
-                    self.title.set_size(size);
-                    self.title.set_text(&text);
-                    self.title.update()?;
-                    self.title.fade_in_out();
+                LevelMessage::ShowTitle(card) => self.titles.show(card)?,
+                LevelMessage::Dialogue { lines, pause_fire } => {
+                    self.dialogue.start(lines, pause_fire)?;
+                }
+                LevelMessage::Done if self.playtest.is_some() => {
+                    return Ok(Some(Message::SwitchScene(super::GameScene::Menu)));
                 }
                 LevelMessage::Done => {
-                    self.state.stage += 1;
+                    self.state.submit_score(self.stage, self.stage_score);
+                    // Replaying an earlier stage should not lose progress.
+                    self.state.stage = self.state.stage.max(self.stage + 1);
+                    self.stage += 1;
                     self.state.save()?;
                     self.level = None;
                 }
                 _ => (),
             }
         } else {
-            self.level = match self.state.stage {
-                0 => Some(tutorial(&self.layers)?),
-                1 => None,
-                _ => None,
+            self.level = match (&self.playtest, self.endless) {
+                (Some((stage, start)), _) => Some(stage.level(&self.layers)?.starting_at(start)?),
+                (None, Some(seed)) => Some(stages::endless(&self.layers, seed)?),
+                (None, None) => stages::load(self.stage, &self.layers)?,
             };
+            if let Some(level) = self.level.as_mut() {
+                level.set_difficulty(self.state.difficulty);
+            }
+            self.stage_score = 0;
         }
-        self.title.update()?;
+        self.titles.update()?;
+        self.dialogue.update(&mut self.camera)?;
+        timer.lap(Section::Level);
 
+        // Spawned after the loop, since the projectiles can not be pushed to while iterating them.
+        let mut kills = vec![];
+        let mut splits = vec![];
         self.projectiles.retain_mut(|projectile| {
             if projectile.age().elapsed() > Duration::from_secs(20) {
                 projectile.remove();
@@ -213,54 +604,75 @@ impl Loop {
 
             let touching = projectile.touching();
             if projectile.friendly() {
-                let mut hit = false;
-                self.enemies.retain_mut(|enemy| {
-                    let touch = enemy.damage_if_id_right(&touching, projectile.damage());
-                    if touch.1 {
-                        hit = true;
-                        if touch.0 <= 0.0 {
-                            // kill
-                            if let Some(level) = self.level.as_mut() {
-                                level.kill();
-                                self.state.kills += 1;
-                                self.state.score += (projectile.damage() * 100.0) as u32;
-                                self.score.text = self.state.score.to_string();
-                            }
-                            enemy.remove();
-                            false
-                        } else {
-                            // damage
-                            true
-                        }
-                    } else {
-                        true
+                let Some(index) = self
+                    .enemies
+                    .iter()
+                    .position(|enemy| touching.contains(&enemy.id()))
+                else {
+                    projectile.update();
+                    return true;
+                };
+                let hit = Hit {
+                    kind: projectile.kind(),
+                    position: projectile.position(),
+                    damage: projectile.damage(),
+                    critical: projectile.critical(),
+                    effects: projectile.effects(),
+                };
+                match self.enemies[index].hit(&hit) {
+                    HitOutcome::Hit => (),
+                    HitOutcome::Killed(kill) => {
+                        kills.push((self.enemies.swap_remove(index), kill));
+                    }
+                    HitOutcome::Blocked => {
+                        // Blocked shots glance off away from the enemy.
+                        let away = hit.position - self.enemies[index].position();
+                        projectile.rebound(away.try_normalize().unwrap_or(Vec2::X));
+                        projectile.update();
+                        return true;
                     }
-                });
-                if hit {
-                    projectile.remove();
-                    return false;
                 }
+                projectile.remove();
+                return false;
             } else {
                 // damage
                 let position = projectile.position();
                 if position.x < 0.0 {
-                    self.paddle.damage(projectile.damage());
+                    if !self.powerups.consume(PowerUpKind::Shield) {
+                        self.paddle.damage(projectile.damage());
+                    }
                     projectile.remove();
                     self.camera.shake();
                     self.sounds.damage.play().unwrap();
                     return false;
                 }
 
-                // send it to the arrow
-                if touching.contains(self.paddle.object.id()) {
-                    if position.x < 0.1 {
+                let parried = self.abilities.parrying()
+                    && position.distance(self.paddle.object.transform.position) < PARRY_RANGE;
+                if parried {
+                    self.sounds.critical.play().unwrap();
+                    projectile.damage_multiplier(PARRY_BONUS);
+                    projectile.parry();
+                    projectile.rebound(self.paddle.rebound_direction());
+                } else if touching.contains(self.paddle.object.id()) {
+                    // send it to the arrow
+                    let direction = if position.x < 0.1 {
                         // hard shot
+                        self.state.hard_shots += 1;
                         self.sounds.critical.play().unwrap();
                         self.camera.shake();
                         projectile.damage_multiplier(2.0);
-                        projectile.rebound(self.paddle.rebound_direction() * 2.0);
+                        projectile.set_critical(true);
+                        self.paddle.rebound_direction() * 2.0
                     } else {
-                        projectile.rebound(self.paddle.rebound_direction());
+                        self.paddle.rebound_direction()
+                    };
+                    projectile.rebound(direction);
+                    if self.powerups.is_active(PowerUpKind::Split) {
+                        for angle in [-SPLIT_ANGLE, SPLIT_ANGLE] {
+                            let direction = Vec2::from_angle(angle).rotate(direction);
+                            splits.push(projectile.split(direction));
+                        }
                     }
                 }
             }
@@ -268,12 +680,24 @@ impl Loop {
 
             true
         });
+        for split in splits {
+            self.projectiles.push(split?);
+        }
+        for (enemy, kill) in kills {
+            self.kill(enemy, kill)?;
+        }
+        timer.lap(Section::Projectiles);
 
-        for enemy in &mut self.enemies {
+        let mut index = 0;
+        while index < self.enemies.len() {
+            let enemy = &mut self.enemies[index];
             let message = enemy.update();
+            index += 1;
 
             match message {
                 EnemyMessage::None => (),
+                // Enemies hold their fire while a dialogue asks them to.
+                EnemyMessage::Shoot { .. } if self.dialogue.pauses_fire() => (),
                 EnemyMessage::Shoot {
                     projectile_type,
                     position,
@@ -282,33 +706,118 @@ impl Loop {
                     let projectile = projectile_type.spawn(
                         self.background.sky.layer(),
                         position,
-                        direction,
+                        direction * self.state.difficulty.projectile_speed(),
                         &self.sounds,
                     )?;
                     self.projectiles.push(projectile);
                 }
                 EnemyMessage::Particle => (),
+                EnemyMessage::Died(kill) => {
+                    index -= 1;
+                    let enemy = self.enemies.swap_remove(index);
+                    self.kill(enemy, kill)?;
+                }
+                EnemyMessage::Escaped => {
+                    index -= 1;
+                    self.enemies.swap_remove(index).remove();
+                    if let Some(level) = self.level.as_mut() {
+                        level.kill();
+                    }
+                }
             }
         }
+        timer.lap(Section::Enemies);
+
+        self.particles.update()?;
+        timer.lap(Section::Particles);
+
+        if let Some(trajectory) = self.trajectory.as_mut() {
+            trajectory.update(&self.paddle, &self.enemies, self.preview_length)?;
+        }
+        if let Some(debug) = self.debug.as_mut() {
+            debug.update(&self.paddle, &self.enemies, &self.projectiles)?;
+        }
 
         self.background.update()?;
         self.camera.update();
+        self.score
+            .object
+            .appearance
+            .set_transform(layout::label_transform(Vec2::ZERO));
         self.score.sync();
         let message = self.menu.update()?;
+        timer.lap(Section::Scenery);
+        self.section_times = timer;
 
         Ok(message)
     }
 
+    /// Counts the kill, rewards its score and drop and destroys the enemy.
+    fn kill(&mut self, mut enemy: Box<dyn Enemy>, kill: Kill) -> Result<()> {
+        if let Some(level) = self.level.as_mut() {
+            level.kill();
+            self.state.kills += 1;
+            self.state.score += kill.score;
+            self.stage_score += kill.score;
+            self.score.text = self.state.score.to_string();
+        }
+        let position = enemy.position();
+        enemy.destroy(&mut self.particles);
+        if let Some(kind) = kill.drop {
+            self.pickups
+                .push(Pickup::new(&self.layers.main, kind, position)?);
+        }
+        Ok(())
+    }
+
+    /// Moves the pickups and applies the ones the paddle touches.
+    fn update_pickups(&mut self) -> Result<()> {
+        let mut index = 0;
+        while index < self.pickups.len() {
+            let pickup = &mut self.pickups[index];
+            pickup.update()?;
+            let collected = pickup.touching().contains(self.paddle.object.id());
+            if !collected && pickup.position().x > -0.2 {
+                index += 1;
+                continue;
+            }
+            let pickup = self.pickups.swap_remove(index);
+            if collected {
+                match pickup.kind {
+                    PowerUpKind::Health => {
+                        self.paddle.max_health += 1.0;
+                        self.paddle.health = (self.paddle.health + 1.0).min(self.paddle.max_health);
+                    }
+                    kind => self.powerups.activate(kind),
+                }
+            }
+            pickup.remove();
+        }
+        Ok(())
+    }
+
     pub fn event(&mut self, event: &Event) -> Result<()> {
         match event {
             Event::Input(InputEvent::KeyboardInput { input }) => {
-                if let Key::Named(NamedKey::Escape) = input.keycode {
-                    if input.state == ElementState::Pressed {
-                        self.menu.toggle();
+                let pressed = input.state == ElementState::Pressed;
+                match Action::from_input(input) {
+                    Some(Action::Pause) if pressed => self.menu.toggle(),
+                    Some(action @ (Action::Advance | Action::Choose(_)))
+                        if pressed && !self.menu.is_enabled() =>
+                    {
+                        if let Some(flag) = self.dialogue.input(action)? {
+                            self.state.flags.insert(flag);
+                        }
+                    }
+                    // Abilities can not be triggered while paused, but focus should not get stuck.
+                    Some(action) if action == Action::Focus || !self.menu.is_enabled() => {
+                        self.abilities.input(action, pressed, &self.paddle)
                     }
+                    _ => (),
                 }
             }
-            Event::Input(InputEvent::MouseMotion(delta)) => {
+            // Moving the mouse in the pause menu should not move the paddle after resuming.
+            Event::Input(InputEvent::MouseMotion(delta)) if !self.menu.is_enabled() => {
                 self.paddle.delta = *delta;
             }
             Event::Window(WindowEvent::Resized(_)) => {
@@ -324,20 +833,60 @@ pub struct Level {
     enemy_limit: u32,
     enemies: u32,
     event_duration: Duration,
-    last_event: Instant,
-    events: VecDeque<LevelMessage>,
-    events_count: usize,
+    last_event: GameInstant,
+    events: Vec<LevelMessage>,
+    flow: Flow,
+    difficulty: Difficulty,
+    /// Refills the events with new waves once they run out.
+    generator: Option<WaveGenerator>,
 
     progress_bar: Object,
 }
 
+/// Checks the enemies, names and labels the events refer to, so mistakes in a stage show up when it gets loaded
+/// instead of crashing it halfway through.
+pub fn validate(events: &[LevelMessage]) -> Result<()> {
+    labels(events)?;
+    for message in events {
+        match message {
+            LevelMessage::SpawnEnemy(enemy) => Spawn::new(*enemy).validate()?,
+            LevelMessage::Spawn(spawn) => spawn.validate()?,
+            LevelMessage::SpawnGroup(spawns) => {
+                for spawn in spawns {
+                    spawn.validate()?;
+                }
+            }
+            LevelMessage::SpawnFormation {
+                enemy,
+                formation,
+                count,
+                entry,
+                idle,
+                ..
+            } => {
+                if Formation::named(formation, *count).is_none() {
+                    return Err(anyhow!("Unknown formation {formation}"));
+                }
+                Spawn::new(*enemy)
+                    .entry(entry.as_str())
+                    .idle(idle.as_str())
+                    .validate()?;
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
 impl Level {
     pub fn new(
         layers: &Layers,
         enemy_limit: u32,
         event_duration: Duration,
-        events: VecDeque<LevelMessage>,
+        events: Vec<LevelMessage>,
     ) -> Result<Self> {
+        validate(&events)?;
+        let flow = Flow::new(&events)?;
         let appearance = Appearance::new()
             .model(Some(Model::Square))
             .transform(
@@ -356,29 +905,88 @@ impl Level {
             enemy_limit,
             enemies: 0,
             event_duration,
-            last_event: Instant::now(),
-            events_count: events.len(),
+            last_event: GameInstant::now(),
             events,
+            flow,
+            difficulty: Difficulty::default(),
+            generator: None,
             progress_bar,
         })
     }
 
+    /// Makes the level endless generating new waves once all events are done.
+    pub fn with_generator(mut self, generator: WaveGenerator) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    /// Skips all events before the given one.
+    ///
+    /// The waiting time and enemy limit are set as if the skipped events happened.
+    pub fn starting_at(mut self, start: &StartAt) -> Result<Self> {
+        let index = start.resolve(&self.events)?;
+        for message in &self.events[..index] {
+            match message {
+                LevelMessage::ChangeWaitingTime(duration) => self.event_duration = *duration,
+                // Gets scaled once the difficulty is set.
+                LevelMessage::ChangeEnemyLimit(limit) => self.enemy_limit = *limit,
+                _ => (),
+            }
+        }
+        self.flow.cursor = index;
+        self.flow.repeats.clear();
+        self.enemies = 0;
+        self.last_event = GameInstant::now();
+        self.update_progress_bar()?;
+        Ok(self)
+    }
+
+    /// Scales the enemy limit of this level to the difficulty.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.enemy_limit = difficulty.enemy_limit(self.enemy_limit);
+        self.difficulty = difficulty;
+    }
+
+    /// The current wave of an endless level.
+    pub fn wave(&self) -> u32 {
+        self.generator
+            .as_ref()
+            .map(|generator| generator.wave())
+            .unwrap_or(0)
+    }
+
     /// Progresses the current stage.
-    pub fn progress(&mut self) -> Result<LevelMessage> {
+    pub fn progress(&mut self, context: &LevelContext) -> Result<LevelMessage> {
+        if self.flow.cursor >= self.events.len() && self.enemies == 0 {
+            if let Some(generator) = self.generator.as_mut() {
+                self.events = generator.next_wave();
+                self.flow = Flow::new(&self.events)?;
+            }
+        }
+        // Follows the edge of the window when it gets resized.
+        self.update_progress_bar()?;
+        let (enemies, difficulty) = (self.enemies, self.difficulty);
+        match self.flow.run(&self.events, |condition| {
+            condition.check(enemies, difficulty, context)
+        })? {
+            FlowState::Waiting => return Ok(LevelMessage::None),
+            // The next event waits as if the condition was an event.
+            FlowState::Waited => self.last_event = GameInstant::now(),
+            FlowState::Ready => (),
+        }
         if self.enemies < self.enemy_limit && self.last_event.elapsed() > self.event_duration {
-            if let Some(message) = self.events.pop_front() {
-                if let Some(window) = SETTINGS.window() {
-                    self.progress_bar.transform.size.x =
-                        CameraScaling::KeepVertical.scale(window.inner_size()).x
-                            * 0.5
-                            * (self.events.len() as f32 / self.events_count as f32);
-                    self.progress_bar.sync()?;
-                }
+            if let Some(message) = self.events.get(self.flow.cursor).cloned() {
+                self.flow.cursor += 1;
 
-                self.last_event = Instant::now();
+                self.last_event = GameInstant::now();
                 match message {
-                    LevelMessage::SpawnEnemy(_) => self.enemies += 1,
+                    LevelMessage::SpawnEnemy(_) | LevelMessage::Spawn(_) => self.enemies += 1,
+                    LevelMessage::SpawnGroup(ref spawns) => self.enemies += spawns.len() as u32,
+                    LevelMessage::SpawnFormation { count, .. } => self.enemies += count as u32,
                     LevelMessage::ChangeWaitingTime(duration) => self.event_duration = duration,
+                    LevelMessage::ChangeEnemyLimit(limit) => {
+                        self.enemy_limit = self.difficulty.enemy_limit(limit)
+                    }
                     _ => (),
                 }
 
@@ -393,6 +1001,17 @@ impl Level {
         }
     }
 
+    /// Shrinks the bar along the top edge of the window with the remaining events.
+    fn update_progress_bar(&mut self) -> Result<()> {
+        let screen = layout::screen_size();
+        let remaining = self.events.len().saturating_sub(self.flow.cursor);
+        self.progress_bar.transform.position = vec2(0.0, -screen.y);
+        self.progress_bar.transform.size.x =
+            screen.x * 0.5 * (remaining as f32 / self.events.len().max(1) as f32);
+        self.progress_bar.sync()?;
+        Ok(())
+    }
+
     pub fn unload(self) {
         let _ = self.progress_bar.remove();
     }
@@ -402,125 +1021,186 @@ impl Level {
     }
 }
 
-pub enum LevelMessage {
-    None,
-    Done,
-    SpawnEnemy(EnemyType),
-    ChangeWaitingTime(Duration),
-    ShowTitle {
-        color: Color,
-        size: Vec2,
-        text: String,
-    },
-}
+/// Flow control events jumping back and forth more often than this in one frame are a bug.
+const MAX_JUMPS: usize = 1000;
 
-struct Title {
-    back_label: Label<Object>,
-    label: Label<Object>,
+/// What the events of a level can check.
+pub struct LevelContext<'a> {
+    pub health: f32,
+    /// Score made in the current stage.
+    pub score: u32,
+    /// Flags set by dialogue choices.
+    pub flags: &'a HashSet<String>,
 }
 
-impl Title {
-    pub fn new(layer: &Arc<Layer>) -> Result<Self> {
-        let appearance = Appearance::new()
-            .color(Color::BLACK)
-            .transform(Transform::default().size(vec2(0.55, 0.7)));
-
-        let back_label = Label::new(
-            &FONT_STINGRAY,
-            LabelCreateInfo::default()
-                .align(Direction::N)
-                .transform(Transform::default().position(vec2(0.01, 0.01)))
-                .appearance(appearance.clone())
-                .scale(Vec2::splat(70.0)),
-        )
-        .init(layer)?;
-
-        let appearance = appearance.color(Color::WHITE);
-
-        let label = Label::new(
-            &FONT_STINGRAY,
-            LabelCreateInfo::default()
-                .align(Direction::N)
-                .appearance(appearance)
-                .scale(Vec2::splat(70.0)),
-        )
-        .init(layer)?;
+/// Something the flow control events of a level can wait for or branch on.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Condition {
+    /// No spawned enemies are left.
+    EnemiesDead,
+    HealthBelow(f32),
+    ScoreReached(u32),
+    DifficultyAtLeast(Difficulty),
+    /// A dialogue choice set the flag.
+    Flag(String),
+    Not(Box<Condition>),
+}
 
-        Ok(Self { back_label, label })
+impl Condition {
+    fn check(&self, enemies: u32, difficulty: Difficulty, context: &LevelContext) -> bool {
+        match self {
+            Condition::EnemiesDead => enemies == 0,
+            Condition::HealthBelow(health) => context.health < *health,
+            Condition::ScoreReached(score) => context.score >= *score,
+            Condition::DifficultyAtLeast(at_least) => difficulty >= *at_least,
+            Condition::Flag(flag) => context.flags.contains(flag),
+            Condition::Not(condition) => !condition.check(enemies, difficulty, context),
+        }
     }
+}
 
-    pub fn update(&mut self) -> Result<()> {
-        self.back_label.object.update()?;
-        self.label.object.update()?;
-        self.back_label.sync();
-        self.label.sync();
-        Ok(())
+/// Index of the event after every label, making sure every jump and repeat leads to one.
+fn labels(events: &[LevelMessage]) -> Result<HashMap<String, usize>> {
+    let mut labels = HashMap::new();
+    for (index, message) in events.iter().enumerate() {
+        if let LevelMessage::Label(label) = message {
+            if labels.insert(label.clone(), index + 1).is_some() {
+                return Err(anyhow!("Label {label} is used twice"));
+            }
+        }
     }
-
-    pub fn remove(self) {
-        let _ = self.back_label.object.remove();
-        let _ = self.label.object.remove();
+    for message in events {
+        if let LevelMessage::Jump(label)
+        | LevelMessage::JumpIf { label, .. }
+        | LevelMessage::Repeat { label, .. } = message
+        {
+            if !labels.contains_key(label) {
+                return Err(anyhow!("Unknown label {label}"));
+            }
+        }
     }
+    Ok(labels)
+}
 
-    pub fn set_text(&mut self, text: &str) {
-        self.back_label.update_text(text);
-        self.label.update_text(text);
-        self.label.object.sync().unwrap();
-    }
+/// The position in the events of a level and the state of its loops.
+#[derive(Clone, Debug, Default)]
+struct Flow {
+    /// Index of the next event.
+    cursor: usize,
+    /// Remaining repetitions of the active repeat events by their index.
+    repeats: HashMap<usize, u32>,
+    labels: HashMap<String, usize>,
+}
 
-    pub fn set_color(&mut self, color: Color) {
-        self.label.object.appearance.set_color(color);
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FlowState {
+    /// The cursor is at an event that is not flow control or past the last one.
+    Ready,
+    /// A condition the level waits for is not true yet.
+    Waiting,
+    /// Ready after passing a condition that was waited for.
+    Waited,
+}
 
-    pub fn set_size(&mut self, size: Vec2) {
-        self.back_label.scale = size;
-        self.label.scale = size;
+impl Flow {
+    fn new(events: &[LevelMessage]) -> Result<Self> {
+        Ok(Self {
+            labels: labels(events)?,
+            ..Default::default()
+        })
     }
 
-    pub fn fade_in_out(&mut self) {
-        let mut back_label = self.back_label.clone();
-        let mut label = self.label.clone();
-        let duration = Duration::from_secs(1);
-        std::thread::spawn(move || {
-            let now = Instant::now();
-            let start_time = now.elapsed();
-            let time = duration.as_secs_f32();
-            while now.elapsed() <= duration {
-                let elapsed = now.elapsed().as_secs_f32();
-                let percentage = 1.0 - (elapsed - time) / (start_time.as_secs_f32() - time);
-                let _ = back_label.update();
-                let _ = label.update();
-                back_label
-                    .object
-                    .appearance
-                    .get_color_mut()
-                    .set_a(percentage);
-                label.object.appearance.get_color_mut().set_a(percentage);
-                let _ = back_label.object.sync();
-                let _ = label.object.sync();
-            }
-            std::thread::sleep(duration * 2);
-            let now = Instant::now();
-            let start_time = now.elapsed();
-            let time = duration.as_secs_f32();
-            while now.elapsed() <= duration {
-                let elapsed = now.elapsed().as_secs_f32();
-                let percentage = (elapsed - time) / (start_time.as_secs_f32() - time);
-                let _ = back_label.update();
-                let _ = label.update();
-                back_label
-                    .object
-                    .appearance
-                    .get_color_mut()
-                    .set_a(percentage);
-                label.object.appearance.get_color_mut().set_a(percentage);
-                let _ = back_label.object.sync();
-                let _ = label.object.sync();
+    /// Runs the flow control events at the cursor without waiting between them.
+    fn run(
+        &mut self,
+        events: &[LevelMessage],
+        check: impl Fn(&Condition) -> bool,
+    ) -> Result<FlowState> {
+        let mut state = FlowState::Ready;
+        for _ in 0..MAX_JUMPS {
+            let Some(message) = events.get(self.cursor) else {
+                return Ok(state);
+            };
+            match message {
+                LevelMessage::Label(_) => self.cursor += 1,
+                LevelMessage::Jump(label) => self.cursor = self.labels[label],
+                LevelMessage::JumpIf { condition, label } => {
+                    if check(condition) {
+                        self.cursor = self.labels[label];
+                    } else {
+                        self.cursor += 1;
+                    }
+                }
+                LevelMessage::WaitUntil(condition) => {
+                    if !check(condition) {
+                        return Ok(FlowState::Waiting);
+                    }
+                    state = FlowState::Waited;
+                    self.cursor += 1;
+                }
+                LevelMessage::Repeat { label, times } => {
+                    let remaining = self.repeats.entry(self.cursor).or_insert(*times);
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        self.cursor = self.labels[label];
+                    } else {
+                        // Resets the count in case an outer loop comes back here.
+                        self.repeats.remove(&self.cursor);
+                        self.cursor += 1;
+                    }
+                }
+                _ => return Ok(state),
             }
-        });
+        }
+        Err(anyhow!(
+            "Level jumped {MAX_JUMPS} times in a row without any event"
+        ))
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum LevelMessage {
+    None,
+    Done,
+    SpawnEnemy(EnemyType),
+    /// Spawns an enemy with overridden defaults.
+    Spawn(Spawn),
+    /// Spawns all enemies at once, only waiting once for the whole group.
+    SpawnGroup(Vec<Spawn>),
+    /// Spawns a group of enemies moving together along the named paths.
+    SpawnFormation {
+        enemy: EnemyType,
+        formation: String,
+        count: usize,
+        anchor: Vec2,
+        entry: String,
+        idle: String,
+    },
+    ChangeWaitingTime(Duration),
+    ChangeEnemyLimit(u32),
+    ShowTitle(TitleCard),
+    /// A target for jumps.
+    Label(String),
+    Jump(String),
+    JumpIf {
+        condition: Condition,
+        label: String,
+    },
+    /// Holds back the following events until the condition is true.
+    WaitUntil(Condition),
+    /// Jumps back to the label the given amount of times before moving on.
+    Repeat {
+        label: String,
+        times: u32,
+    },
+    /// Shows the lines in a dialogue box, holding back the following events until it is closed.
+    Dialogue {
+        lines: Vec<DialogueLine>,
+        /// Whether enemies stop shooting while the dialogue is shown.
+        pause_fire: bool,
+    },
+}
+
 struct Background {
     sky: Object,
     sun: Object,
@@ -638,7 +1318,7 @@ impl Background {
 
     pub fn update(&mut self) -> Result<()> {
         let size_x = self.sky.appearance.get_transform().size.x * 2.0;
-        let time = TIME.time() as f32 * 0.15;
+        let time = clock::now() as f32 * 0.15;
 
         self.far_clouds.transform.position.x = (size_x - time) % size_x;
         self.hills.transform.position.x = (size_x - time * 1.3) % size_x;
@@ -654,3 +1334,178 @@ impl Background {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_baseline_save() {
+        // The first release saved stage, score and kills with bincode.
+        let data = bincode::serialize(&(3u32, 1200u32, 57u32)).unwrap();
+        let state = GameState::from_legacy(&data).unwrap();
+        assert_eq!((state.stage, state.score, state.kills), (3, 1200, 57));
+        assert!(state.best_scores.is_empty());
+        assert_eq!(state.play_time, 0.0);
+    }
+
+    #[test]
+    fn missing_fields_keep_defaults() {
+        let state = GameState::from_ron("(stage: 2, score: 40)").unwrap();
+        assert_eq!((state.stage, state.score, state.kills), (2, 40, 0));
+        assert_eq!(state.difficulty, Difficulty::default());
+    }
+
+    #[test]
+    fn save_round_trips() {
+        let mut state = GameState {
+            stage: 1,
+            hard_shots: 9,
+            ..Default::default()
+        };
+        state.submit_score(1, 300);
+        state.flags.insert("helped_fairy".to_string());
+        let data = ron::ser::to_string(&state).unwrap();
+        let loaded = GameState::from_ron(&data).unwrap();
+        assert_eq!(loaded.best_score(1), 300);
+        assert_eq!(loaded.hard_shots, 9);
+        assert!(loaded.flags.contains("helped_fairy"));
+    }
+
+    fn spawn() -> LevelMessage {
+        LevelMessage::SpawnEnemy(EnemyType::Target)
+    }
+
+    fn label(name: &str) -> LevelMessage {
+        LevelMessage::Label(name.to_string())
+    }
+
+    /// Plays through the events with every condition being `condition` and returns the indices of
+    /// the events that got played.
+    fn play(events: &[LevelMessage], condition: bool) -> Vec<usize> {
+        let mut flow = Flow::new(events).unwrap();
+        let mut played = vec![];
+        while flow.run(events, |_| condition).unwrap() != FlowState::Waiting
+            && flow.cursor < events.len()
+        {
+            played.push(flow.cursor);
+            flow.cursor += 1;
+        }
+        played
+    }
+
+    #[test]
+    fn jumps_skip_to_label() {
+        let events = [
+            spawn(),
+            LevelMessage::Jump("end".to_string()),
+            spawn(),
+            label("end"),
+            spawn(),
+        ];
+        assert_eq!(play(&events, true), [0, 4]);
+    }
+
+    #[test]
+    fn conditional_jumps() {
+        let events = [
+            LevelMessage::JumpIf {
+                condition: Condition::EnemiesDead,
+                label: "skip".to_string(),
+            },
+            spawn(),
+            label("skip"),
+            spawn(),
+        ];
+        assert_eq!(play(&events, true), [3]);
+        assert_eq!(play(&events, false), [1, 3]);
+    }
+
+    #[test]
+    fn conditions_check_the_context() {
+        let flags = HashSet::from(["helped".to_string()]);
+        let context = LevelContext {
+            health: 1.0,
+            score: 500,
+            flags: &flags,
+        };
+        let check = |condition: Condition| condition.check(2, Difficulty::Hard, &context);
+        assert!(check(Condition::HealthBelow(2.0)));
+        assert!(!check(Condition::HealthBelow(1.0)));
+        assert!(check(Condition::ScoreReached(500)));
+        assert!(!check(Condition::ScoreReached(1000)));
+        assert!(check(Condition::DifficultyAtLeast(Difficulty::Normal)));
+        assert!(!check(Condition::DifficultyAtLeast(Difficulty::Nightmare)));
+        assert!(check(Condition::Flag("helped".to_string())));
+        assert!(check(Condition::Not(Box::new(Condition::EnemiesDead))));
+    }
+
+    #[test]
+    fn repeats_loop_the_given_times() {
+        let events = [
+            label("loop"),
+            spawn(),
+            LevelMessage::Repeat {
+                label: "loop".to_string(),
+                times: 2,
+            },
+            spawn(),
+        ];
+        assert_eq!(play(&events, true), [1, 1, 1, 3]);
+    }
+
+    #[test]
+    fn nested_repeats_reset() {
+        let events = [
+            label("outer"),
+            label("inner"),
+            spawn(),
+            LevelMessage::Repeat {
+                label: "inner".to_string(),
+                times: 1,
+            },
+            LevelMessage::Repeat {
+                label: "outer".to_string(),
+                times: 1,
+            },
+        ];
+        assert_eq!(play(&events, true), [2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn waits_until_condition() {
+        let events = [
+            spawn(),
+            LevelMessage::WaitUntil(Condition::EnemiesDead),
+            spawn(),
+        ];
+        assert_eq!(play(&events, false), [0]);
+
+        let mut flow = Flow::new(&events).unwrap();
+        flow.cursor = 1;
+        assert_eq!(flow.run(&events, |_| false).unwrap(), FlowState::Waiting);
+        assert_eq!(flow.cursor, 1);
+        assert_eq!(flow.run(&events, |_| true).unwrap(), FlowState::Waited);
+        assert_eq!(flow.cursor, 2);
+    }
+
+    #[test]
+    fn endless_jumps_are_an_error() {
+        let events = [label("loop"), LevelMessage::Jump("loop".to_string())];
+        assert!(Flow::new(&events).unwrap().run(&events, |_| true).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_labels() {
+        assert!(validate(&[LevelMessage::Jump("nowhere".to_string())]).is_err());
+        assert!(validate(&[label("twice"), label("twice")]).is_err());
+        assert!(validate(&[
+            label("here"),
+            LevelMessage::Repeat {
+                label: "here".to_string(),
+                times: 1,
+            },
+        ])
+        .is_ok());
+    }
+}
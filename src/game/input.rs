@@ -0,0 +1,56 @@
+use let_engine::prelude::*;
+
+/// Things the player can do ingame bound to keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Pause,
+    Dash,
+    Parry,
+    Focus,
+    /// Shows the next line of a dialogue.
+    Advance,
+    /// Picks the dialogue choice with the given number.
+    Choose(u8),
+}
+
+impl Action {
+    /// The action bound to the key of the keyboard input.
+    pub fn from_input(input: &KeyboardInput) -> Option<Self> {
+        // Choices use the number row by position, since holding shift to focus turns it into
+        // symbols that differ between layouts.
+        match input.physical_key {
+            PhysicalKey::Code(KeyCode::Digit1) => Some(Action::Choose(1)),
+            PhysicalKey::Code(KeyCode::Digit2) => Some(Action::Choose(2)),
+            PhysicalKey::Code(KeyCode::Digit3) => Some(Action::Choose(3)),
+            PhysicalKey::Code(KeyCode::Digit4) => Some(Action::Choose(4)),
+            _ => Self::from_key(&input.keycode),
+        }
+    }
+
+    /// The action bound to the given key.
+    fn from_key(key: &Key) -> Option<Self> {
+        match key {
+            Key::Named(NamedKey::Escape) => Some(Action::Pause),
+            Key::Named(NamedKey::Space) => Some(Action::Dash),
+            Key::Named(NamedKey::Shift) => Some(Action::Focus),
+            Key::Named(NamedKey::Enter) => Some(Action::Advance),
+            Key::Character(character) if character.eq_ignore_ascii_case("e") => Some(Action::Parry),
+            _ => None,
+        }
+    }
+
+    /// The name of the key this action is bound to.
+    pub fn key_name(&self) -> &'static str {
+        match self {
+            Action::Pause => "Esc",
+            Action::Dash => "Space",
+            Action::Parry => "E",
+            Action::Focus => "Shift",
+            Action::Advance => "Enter",
+            Action::Choose(1) => "1",
+            Action::Choose(2) => "2",
+            Action::Choose(3) => "3",
+            Action::Choose(_) => "4",
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Placement of the UI relative to the edges of the window.
+//!
+//! UI placed around the center is laid out for the reference resolution and scaled down to fit
+//! narrower windows. Anchored UI sticks to the corners and edges of the window instead.
+use std::sync::RwLock;
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+static LAYOUT: RwLock<(Layout, Vec2)> = RwLock::new((Layout::new(), Vec2::new(455.0, 256.0)));
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Layout {
+    /// Multiplier of the size of the whole UI.
+    pub ui_scale: f32,
+    /// Margin kept free at the edges of the window as a fraction of its height.
+    pub safe_area: f32,
+}
+
+impl Layout {
+    const fn new() -> Self {
+        Self {
+            ui_scale: 1.0,
+            safe_area: 0.02,
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A point of the safe area UI elements can stick to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// The side of the safe area on each axis, from -1 for left and top to 1 for right and bottom.
+    fn side(&self) -> Vec2 {
+        match self {
+            Anchor::TopLeft => vec2(-1.0, -1.0),
+            Anchor::Top => vec2(0.0, -1.0),
+            Anchor::TopRight => vec2(1.0, -1.0),
+            Anchor::Left => vec2(-1.0, 0.0),
+            Anchor::Center => vec2(0.0, 0.0),
+            Anchor::Right => vec2(1.0, 0.0),
+            Anchor::BottomLeft => vec2(-1.0, 1.0),
+            Anchor::Bottom => vec2(0.0, 1.0),
+            Anchor::BottomRight => vec2(1.0, 1.0),
+        }
+    }
+
+    /// The label alignment keeping text inside the safe area at this anchor.
+    pub fn align(&self) -> Direction {
+        match self {
+            Anchor::TopLeft => Direction::Nw,
+            Anchor::Top => Direction::N,
+            Anchor::TopRight => Direction::Ne,
+            Anchor::Left => Direction::W,
+            Anchor::Center => Direction::Center,
+            Anchor::Right => Direction::E,
+            Anchor::BottomLeft => Direction::Sw,
+            Anchor::Bottom => Direction::S,
+            Anchor::BottomRight => Direction::Se,
+        }
+    }
+}
+
+pub fn get() -> Layout {
+    LAYOUT.read().unwrap().0
+}
+
+/// Applies the layout and the resolution the UI is laid out for.
+pub fn set(layout: Layout, resolution: Vec2) {
+    *LAYOUT.write().unwrap() = (layout, resolution);
+}
+
+/// The zoom of the UI layer scaling it by the UI scale and down to fit windows narrower than the
+/// reference resolution.
+pub fn zoom() -> f32 {
+    let (layout, resolution) = *LAYOUT.read().unwrap();
+    let fit = SETTINGS
+        .window()
+        .map(|window| {
+            let size = window.inner_size();
+            let aspect = size.x / size.y.max(1.0);
+            (aspect / (resolution.x / resolution.y)).min(1.0)
+        })
+        .unwrap_or(1.0);
+    (layout.ui_scale * fit).max(0.01)
+}
+
+/// Zooms the UI layer to the current window and layout.
+pub fn apply(layer: &Layer) {
+    layer.set_zoom(zoom());
+}
+
+/// Half the size of the window in UI layer coordinates.
+pub fn screen_size() -> Vec2 {
+    SETTINGS
+        .window()
+        .map(|window| CameraScaling::KeepVertical.scale(window.inner_size()))
+        .unwrap_or(vec2(1.0, 1.0))
+        / zoom()
+}
+
+/// Half the size of the window without the safe area margin.
+pub fn safe_size() -> Vec2 {
+    let size = screen_size();
+    size - Vec2::splat(size.y * 2.0 * get().safe_area)
+}
+
+/// The position of the anchor in the safe area moved by the offset.
+pub fn position(anchor: Anchor, offset: Vec2) -> Vec2 {
+    anchor.side() * safe_size() + offset
+}
+
+/// A label box filling the safe area, moved by the offset.
+///
+/// Aligning the label with the anchor puts the text at the anchor.
+pub fn label_transform(offset: Vec2) -> Transform {
+    Transform::default().position(offset).size(safe_size())
+}
@@ -0,0 +1,153 @@
+//! Translations of the text shown in the game.
+use std::{collections::HashMap, sync::RwLock};
+
+use anyhow::{anyhow, Result};
+use let_engine::prelude::*;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::{FONT_RAWR, FONT_STINGRAY};
+
+use super::GameSettings;
+
+static LOCALE: Lazy<RwLock<Locale>> = Lazy::new(|| RwLock::new(Locale::english()));
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// The ISO 639-1 code the language file is named after.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    /// The language after this one, wrapping around to the first.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|language| language == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    /// The language file shipped with the game.
+    fn builtin(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../assets/lang/en.ron"),
+            Language::German => include_str!("../../assets/lang/de.ron"),
+        }
+    }
+}
+
+/// One of the fonts shipped with the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FontChoice {
+    Stingray,
+    Rawr,
+}
+
+impl FontChoice {
+    fn font(&self) -> &'static Font {
+        match self {
+            FontChoice::Stingray => &FONT_STINGRAY,
+            FontChoice::Rawr => &FONT_RAWR,
+        }
+    }
+}
+
+/// The contents of a language file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct LanguageFile {
+    /// Font of normal text.
+    text_font: FontChoice,
+    /// Font of buttons and headings.
+    display_font: FontChoice,
+    strings: HashMap<String, String>,
+}
+
+impl LanguageFile {
+    /// Loads the language file from the config directory if there is one to allow translating
+    /// without rebuilding the game.
+    fn load(language: Language) -> Result<Self> {
+        let path = GameSettings::config_dir()?
+            .join("lang")
+            .join(format!("{}.ron", language.code()));
+        let file = if path.exists() {
+            std::fs::read_to_string(path)?
+        } else {
+            language.builtin().to_string()
+        };
+        ron::from_str(&file)
+            .map_err(|error| anyhow!("Invalid language file {}: {error}", language.code()))
+    }
+}
+
+struct Locale {
+    file: LanguageFile,
+    /// English strings used for keys missing from the language.
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    fn english() -> Self {
+        let file: LanguageFile = ron::from_str(Language::English.builtin())
+            .expect("The built-in English language file is invalid.");
+        Self {
+            fallback: file.strings.clone(),
+            file,
+        }
+    }
+}
+
+/// Switches all text created from now on to the given language.
+pub fn set_language(language: Language) -> Result<()> {
+    let file = LanguageFile::load(language)?;
+    LOCALE.write().unwrap().file = file;
+    Ok(())
+}
+
+/// The translation of the key, falling back to English and then to the key itself.
+///
+/// Text written directly into stages is not a key, so it gets shown as it is.
+pub fn text(key: &str) -> String {
+    let locale = LOCALE.read().unwrap();
+    locale
+        .file
+        .strings
+        .get(key)
+        .or_else(|| locale.fallback.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// The translation of the key with every `{name}` replaced by its value.
+pub fn text_with(key: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(text(key), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// The font of normal text in the current language.
+pub fn font() -> &'static Font {
+    LOCALE.read().unwrap().file.text_font.font()
+}
+
+/// The font of buttons and headings in the current language.
+pub fn display_font() -> &'static Font {
+    LOCALE.read().unwrap().file.display_font.font()
+}
@@ -1,14 +1,13 @@
-use std::time::Duration;
-
 use crate::{
-    objects::{
-        button::{Button, Parent},
-        fade::fade_in,
-    },
-    FONT_STINGRAY, HEIGHT,
+    objects::button::{Button, Parent},
+    HEIGHT,
 };
 
-use super::{load_material, Layers, Message};
+use super::{
+    accessibility,
+    layout::{self, Anchor},
+    load_material, locale, GameScene, Layers, Message,
+};
 use anyhow::Result;
 use let_engine::prelude::*;
 
@@ -20,8 +19,11 @@ pub struct MainMenu {
     birds: Object,
     rainbow: Object,
     play_button: Button,
+    stages_button: Button,
     settings_button: Button,
     quit_button: Button,
+    statistics_button: Button,
+    credits_button: Button,
     version_number: Label<Object>,
 }
 
@@ -104,42 +106,75 @@ impl MainMenu {
             button_material.clone(),
             Some(
                 LabelCreateInfo::default()
-                    .text("Play")
-                    .scale(vec2(60.0, 60.0))
+                    .text(locale::text("menu.play"))
+                    .scale(accessibility::text_size(vec2(60.0, 60.0)))
+                    .align(Direction::Center),
+            ),
+            vec2(0.0, 0.25),
+        )?;
+        let stages_button = Button::new(
+            Parent::Layer(&layers.ui),
+            button_material.clone(),
+            Some(
+                LabelCreateInfo::default()
+                    .text(locale::text("menu.stages"))
+                    .scale(accessibility::text_size(vec2(60.0, 60.0)))
                     .align(Direction::Center),
             ),
-            vec2(0.0, 0.3),
+            vec2(0.0, 0.45),
         )?;
         let settings_button = Button::new(
             Parent::Layer(&layers.ui),
             button_material.clone(),
             Some(
                 LabelCreateInfo::default()
-                    .text("Settings")
-                    .scale(vec2(60.0, 60.0))
+                    .text(locale::text("menu.settings"))
+                    .scale(accessibility::text_size(vec2(60.0, 60.0)))
                     .align(Direction::Center),
             ),
-            vec2(0.0, 0.55),
+            vec2(0.0, 0.65),
         )?;
         let quit_button = Button::new(
+            Parent::Layer(&layers.ui),
+            button_material.clone(),
+            Some(
+                LabelCreateInfo::default()
+                    .text(locale::text("menu.quit"))
+                    .scale(accessibility::text_size(vec2(60.0, 60.0)))
+                    .align(Direction::Center),
+            ),
+            vec2(0.0, 0.85),
+        )?;
+        let statistics_button = Button::new(
+            Parent::Layer(&layers.ui),
+            button_material.clone(),
+            Some(
+                LabelCreateInfo::default()
+                    .text(locale::text("menu.statistics"))
+                    .scale(accessibility::text_size(vec2(45.0, 45.0)))
+                    .align(Direction::Center),
+            ),
+            vec2(-0.75, 0.85),
+        )?;
+        let credits_button = Button::new(
             Parent::Layer(&layers.ui),
             button_material,
             Some(
                 LabelCreateInfo::default()
-                    .text("Quit")
-                    .scale(vec2(60.0, 60.0))
+                    .text(locale::text("menu.credits"))
+                    .scale(accessibility::text_size(vec2(45.0, 45.0)))
                     .align(Direction::Center),
             ),
-            vec2(0.0, 0.8),
+            vec2(0.75, 0.85),
         )?;
 
         let version_number = Label::new(
-            &FONT_STINGRAY,
+            locale::font(),
             LabelCreateInfo::default()
                 .text(env!("CARGO_PKG_VERSION"))
-                .scale(vec2(30.0, 30.0))
-                .transform(Transform::default().position(vec2(0.0, 0.0)))
-                .align(Direction::Sw),
+                .scale(accessibility::text_size(vec2(30.0, 30.0)))
+                .appearance(Appearance::default().transform(layout::label_transform(Vec2::ZERO)))
+                .align(Anchor::BottomLeft.align()),
         )
         .init(&layers.ui)?;
 
@@ -147,8 +182,6 @@ impl MainMenu {
             window.set_cursor_visible(true);
         }
 
-        fade_in(Duration::from_secs(5), layers);
-
         Ok(Self {
             layers: layers.clone(),
             background,
@@ -157,8 +190,11 @@ impl MainMenu {
             title,
             rainbow,
             play_button,
+            stages_button,
             settings_button,
             quit_button,
+            statistics_button,
+            credits_button,
             version_number,
         })
     }
@@ -170,8 +206,11 @@ impl MainMenu {
         let _ = self.title.remove();
         let _ = self.birds.remove();
         self.play_button.remove();
+        self.stages_button.remove();
         self.settings_button.remove();
         self.quit_button.remove();
+        self.statistics_button.remove();
+        self.credits_button.remove();
         let _ = self.version_number.object.remove();
     }
 
@@ -191,22 +230,25 @@ impl MainMenu {
             .appearance
             .set_layer((TIME.time() % 1.0).round() as u32)?;
 
-        if let Some(window) = SETTINGS.window() {
-            self.version_number
-                .object
-                .appearance
-                .get_transform_mut()
-                .size = CameraScaling::KeepVertical.scale(window.inner_size());
-            self.version_number.sync();
-        }
+        self.version_number
+            .object
+            .appearance
+            .set_transform(layout::label_transform(Vec2::ZERO));
+        self.version_number.sync();
 
         self.play_button
-            .on_release(|| message = Some(Message::SwitchScene(super::GameScene::Ingame)));
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Ingame)));
+        self.stages_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::StageSelect)));
         self.settings_button
             .on_release(|| message = Some(Message::ShowSettings(true)));
         self.quit_button.on_release(|| {
             message = Some(Message::Exit);
         });
+        self.statistics_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Statistics)));
+        self.credits_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Credits)));
 
         self.title.sync()?;
         self.background.sync()?;
@@ -216,3 +258,40 @@ impl MainMenu {
         Ok(message)
     }
 }
+
+/// A plain dark background for menu scenes.
+pub fn backdrop(layers: &Layers) -> Result<Object> {
+    Ok(NewObjectBuilder::default()
+        .appearance(
+            Appearance::new()
+                .model(Some(Model::Square))
+                .transform(Transform::default().size(vec2(10.0, 10.0)))
+                .color(Color::from_rgb(10.0 / 255.0, 22.0 / 255.0, 48.0 / 255.0)),
+        )
+        .build()?
+        .init(&layers.main)?)
+}
+
+/// The heading at the top of menu scenes with the translation of the key.
+pub fn scene_title(layers: &Layers, key: &str) -> Result<Label<Object>> {
+    Ok(Label::new(
+        locale::display_font(),
+        LabelCreateInfo::default()
+            .text(locale::text(key))
+            .align(Direction::N)
+            .transform(Transform::default().size(vec2(1.0, 0.95)))
+            .scale(accessibility::text_size(vec2(80.0, 80.0))),
+    )
+    .init(&layers.ui)?)
+}
+
+/// The button leading back to the main menu.
+pub fn back_button(layers: &Layers) -> Result<Button> {
+    Button::new(
+        Parent::Layer(&layers.ui),
+        load_material(&asset("textures/ui/back.png")?, 1),
+        None,
+        Vec2::ZERO,
+    )
+    .map(|button| button.anchored(Anchor::TopLeft, vec2(0.2, 0.15)))
+}
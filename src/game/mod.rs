@@ -1,16 +1,30 @@
-use self::console::Console;
+use self::{console::Console, editor::StageEditor, perf::PerfHud};
 
-use super::objects::Objects;
+use super::objects::{
+    clock,
+    transition::{SceneTransition, Transition, TransitionState},
+    Objects,
+};
 use anyhow::{anyhow, Result};
 use let_engine::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
+pub mod accessibility;
 mod console;
+mod credits;
+pub mod difficulty;
+mod editor;
 mod game_loop;
+pub mod input;
+pub mod layout;
+pub mod locale;
 mod main_menu;
+mod perf;
 pub mod sounds;
+mod stage_select;
 pub mod stages;
+mod statistics;
 
 pub const SAMPLER: Sampler = Sampler {
     mag_filter: Filter::Nearest,
@@ -25,11 +39,22 @@ pub const SAMPLER: Sampler = Sampler {
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct GameSettings {
     pub vsync: bool,
     pub fps_limit: u32,
     pub fullscreen: Fullscreen,
+    /// The resolution the UI is laid out for. Narrower windows scale the UI down to fit.
     pub resolution: Vec2,
+    /// Opens the pause menu when the window loses focus or gets minimized.
+    pub pause_on_focus_loss: bool,
+    /// Seconds of flight the trajectory of a returned shot is previewed for. Zero disables it.
+    pub trajectory_preview: f32,
+    /// Slowly turns the arrow towards the nearest enemy.
+    pub aim_assist: bool,
+    pub language: locale::Language,
+    pub accessibility: accessibility::Accessibility,
+    pub layout: layout::Layout,
 }
 
 impl Default for GameSettings {
@@ -39,6 +64,12 @@ impl Default for GameSettings {
             fullscreen: Fullscreen::Exclusive,
             fps_limit: 0,
             resolution: vec2(455.0, 256.0),
+            pause_on_focus_loss: true,
+            trajectory_preview: 1.5,
+            aim_assist: false,
+            language: locale::Language::English,
+            accessibility: accessibility::Accessibility::default(),
+            layout: layout::Layout::default(),
         }
     }
 }
@@ -115,10 +146,16 @@ pub struct Game {
     layers: Layers,
     objects: Objects,
     console: Console,
+    editor: StageEditor,
+    perf_hud: PerfHud,
 
     settings: GameSettings,
+    show_colliders: bool,
 
     scene: Scene,
+    transition: Option<SceneTransition>,
+    /// What the next ingame scene plays instead of the saved stage.
+    run: Option<game_loop::Run>,
 
     exit: bool,
 }
@@ -126,12 +163,20 @@ pub struct Game {
 impl Game {
     pub fn new(settings: GameSettings) -> Result<Self> {
         let layers = Layers::new();
+        // Fade in the menu on startup.
+        let transition =
+            SceneTransition::reveal(&layers, Transition::Fade(Duration::from_secs(5)))?;
         Ok(Self {
             objects: Objects::new(&layers, settings)?,
             console: Console::new(settings),
+            editor: StageEditor::new(),
+            perf_hud: PerfHud::new(),
             // Start with menu scene
             scene: Scene::Menu(main_menu::MainMenu::new(&layers)?),
+            transition: Some(transition),
+            run: None,
             settings,
+            show_colliders: false,
             layers,
             exit: false,
         })
@@ -145,7 +190,24 @@ impl Game {
                 std::mem::replace(&mut self.scene, scene)
             }
             GameScene::Ingame => {
-                let scene = Scene::Ingame(game_loop::Loop::new(&self.layers)?);
+                let mut game_loop = game_loop::Loop::new(&self.layers)?;
+                game_loop.show_colliders(self.show_colliders)?;
+                game_loop.apply_settings(&self.settings)?;
+                if let Some(run) = self.run.take() {
+                    game_loop.start(run);
+                }
+                std::mem::replace(&mut self.scene, Scene::Ingame(game_loop))
+            }
+            GameScene::StageSelect => {
+                let scene = Scene::StageSelect(stage_select::StageSelect::new(&self.layers)?);
+                std::mem::replace(&mut self.scene, scene)
+            }
+            GameScene::Credits => {
+                let scene = Scene::Credits(credits::Credits::new(&self.layers)?);
+                std::mem::replace(&mut self.scene, scene)
+            }
+            GameScene::Statistics => {
+                let scene = Scene::Statistics(statistics::Statistics::new(&self.layers)?);
                 std::mem::replace(&mut self.scene, scene)
             }
         };
@@ -154,6 +216,9 @@ impl Game {
         match scene {
             Scene::Menu(menu) => menu.unload(),
             Scene::Ingame(game_loop) => game_loop.unload(),
+            Scene::StageSelect(stage_select) => stage_select.unload(),
+            Scene::Credits(credits) => credits.unload(),
+            Scene::Statistics(statistics) => statistics.unload(),
         }
 
         // Optimize memory performance
@@ -162,22 +227,123 @@ impl Game {
         Ok(())
     }
 
+    /// Rebuilds the text on screen in the current language and text size.
+    fn rebuild_text(&mut self) -> Result<()> {
+        self.objects.rebuild_settings(&self.layers, self.settings)?;
+        match &mut self.scene {
+            // Rebuilding the whole stage would restart it.
+            Scene::Ingame(game_loop) => game_loop.rebuild_menu(),
+            scene => {
+                let scene = scene.kind();
+                self.switch_scene(&scene)
+            }
+        }
+    }
+
+    /// Switches to the given scene once the transition covered the screen.
+    pub fn start_transition(&mut self, scene: GameScene, transition: Transition) -> Result<()> {
+        if let Some(current) = self.transition.take() {
+            if current.is_switching() {
+                // Do not interrupt a scene switch that is already happening.
+                self.transition = Some(current);
+                return Ok(());
+            }
+            current.remove();
+        }
+        if transition == Transition::Instant {
+            return self.switch_scene(&scene);
+        }
+        self.transition = Some(SceneTransition::new(&self.layers, transition, scene)?);
+        Ok(())
+    }
+
+    fn update_transition(&mut self) {
+        let Some(transition) = self.transition.as_mut() else {
+            return;
+        };
+        match transition.update() {
+            Ok(TransitionState::Playing) => (),
+            Ok(TransitionState::Covered(scene)) => {
+                if let Err(error) = self.switch_scene(&scene) {
+                    self.console
+                        .print(format!("Error: Could not switch scene.\n{error}"));
+                }
+            }
+            Ok(TransitionState::Finished) => {
+                if let Some(transition) = self.transition.take() {
+                    transition.remove();
+                }
+            }
+            Err(error) => crash("Failed to update scene transition", &error.to_string()),
+        }
+    }
+
     pub fn execute_message(&mut self, message: Message) {
         match message {
             Message::Exit => self.exit = true,
             Message::ShowSettings(show) => self.objects.settings.show(show),
             Message::SwitchScene(scene) => {
-                if let Err(error) = self.switch_scene(&scene) {
+                if let Err(error) = self.start_transition(scene, scene.transition()) {
+                    self.console
+                        .print(format!("Error: Could not switch scene.\n{error}"));
+                }
+            }
+            Message::SwitchSceneWith(scene, transition) => {
+                if let Err(error) = self.start_transition(scene, transition) {
+                    self.console
+                        .print(format!("Error: Could not switch scene.\n{error}"));
+                }
+            }
+            Message::Play(run) => {
+                self.run = Some(run);
+                if let Err(error) =
+                    self.start_transition(GameScene::Ingame, GameScene::Ingame.transition())
+                {
                     self.console
                         .print(format!("Error: Could not switch scene.\n{error}"));
                 }
             }
+            Message::ToggleDebug(DebugView::Colliders) => {
+                self.show_colliders = !self.show_colliders;
+                if let Scene::Ingame(scene) = &mut self.scene {
+                    if let Err(error) = scene.show_colliders(self.show_colliders) {
+                        self.console
+                            .print(format!("Error: Could not show colliders.\n{error}"));
+                    }
+                }
+            }
+            Message::ToggleDebug(DebugView::Performance) => self.perf_hud.toggle(),
+            Message::ToggleEditor => self.editor.toggle(),
+            Message::ToggleDebug(DebugView::FrameLog) => match self.perf_hud.toggle_logging() {
+                Ok(Some(path)) => self
+                    .console
+                    .print(format!("Logging frame stats to {path:?}")),
+                Ok(None) => self
+                    .console
+                    .print("Stopped logging frame stats.".to_string()),
+                Err(error) => self
+                    .console
+                    .print(format!("Error: Could not log frame stats.\n{error}")),
+            },
             Message::ChangeLevel(level) => {
                 if let Scene::Ingame(scene) = &mut self.scene {
-                    scene.state.stage = level as u32;
+                    scene.stage = level as u32;
                 }
             }
-            Message::ApplySettings(settings) => {
+            Message::ApplySettings(mut settings) => {
+                if settings.language != self.settings.language {
+                    if let Err(error) = locale::set_language(settings.language) {
+                        self.console
+                            .print(format!("Failed to change language: {error}"));
+                        // Keep the language that is still in use.
+                        settings.language = self.settings.language;
+                    }
+                }
+                let rebuild_text = settings.language != self.settings.language
+                    || settings.accessibility.text_scale != self.settings.accessibility.text_scale;
+                accessibility::set(settings.accessibility);
+                layout::set(settings.layout, settings.resolution);
+                layout::apply(&self.layers.ui);
                 self.settings = settings;
 
                 if settings.vsync {
@@ -209,7 +375,19 @@ impl Game {
                 }
 
                 SETTINGS.graphics.set_fps_cap(settings.fps_limit as u64);
+                if let Scene::Ingame(scene) = &mut self.scene {
+                    if let Err(error) = scene.apply_settings(&self.settings) {
+                        self.console.print(error.to_string());
+                    }
+                }
                 self.console.settings = self.settings;
+                self.objects.settings.set(self.settings);
+                if rebuild_text {
+                    if let Err(error) = self.rebuild_text() {
+                        self.console
+                            .print(format!("Error: Could not rebuild the text.\n{error}"));
+                    }
+                }
                 if let Err(error) = self.settings.save() {
                     self.console.print(format!("Failed to save game: {error}"));
                 };
@@ -223,6 +401,8 @@ impl let_engine::Game for Game {
         self.exit
     }
     fn update(&mut self) {
+        clock::tick();
+        self.update_transition();
         match self.scene.update() {
             Ok(Some(message)) => self.execute_message(message),
             Err(error) => crash("Failed to update scene", &error.to_string()),
@@ -231,6 +411,16 @@ impl let_engine::Game for Game {
         if let Some(message) = self.objects.update() {
             self.execute_message(message);
         }
+
+        let report = match &self.scene {
+            Scene::Ingame(game_loop) => game_loop.frame_report(),
+            _ => Default::default(),
+        };
+        if let Err(error) = self.perf_hud.record(report) {
+            self.console
+                .print(format!("Error: Could not log frame stats.\n{error}"));
+            let _ = self.perf_hud.toggle_logging();
+        }
     }
     fn tick(&mut self) {
         self.objects.tick_update();
@@ -249,6 +439,10 @@ impl let_engine::Game for Game {
                 if let Some(message) = self.console.update(&ctx) {
                     self.execute_message(message);
                 }
+                if let Some(message) = self.editor.update(&ctx) {
+                    self.execute_message(message);
+                }
+                self.perf_hud.update(&ctx);
             }
             Event::Input(InputEvent::KeyboardInput { input }) => {
                 if let Key::Named(NamedKey::F7) = input.keycode {
@@ -258,7 +452,19 @@ impl let_engine::Game for Game {
                     }
                 }
             }
-            Event::Window(WindowEvent::Resized(_)) => {
+            Event::Window(WindowEvent::Focused(focused)) => {
+                if let Scene::Ingame(game) = &mut self.scene {
+                    game.set_focused(focused, self.settings.pause_on_focus_loss);
+                }
+            }
+            Event::Window(WindowEvent::Resized(size)) => {
+                // Minimizing resizes the window to nothing.
+                if size.x == 0.0 || size.y == 0.0 {
+                    if let Scene::Ingame(game) = &mut self.scene {
+                        game.set_focused(false, self.settings.pause_on_focus_loss);
+                    }
+                }
+                layout::apply(&self.layers.ui);
                 self.objects.camera.update();
             }
             Event::Window(WindowEvent::CloseRequested) => {
@@ -273,6 +479,8 @@ impl let_engine::Game for Game {
 pub struct Layers {
     pub main: Arc<Layer>,
     pub ui: Arc<Layer>,
+    /// Drawn above everything else for scene transitions.
+    pub overlay: Arc<Layer>,
 }
 
 impl Layers {
@@ -287,7 +495,12 @@ impl Layers {
             zoom: 1.0,
             mode: CameraScaling::KeepVertical,
         });
-        Self { main, ui }
+        let overlay = SCENE.new_layer();
+        overlay.set_camera_settings(CameraSettings {
+            zoom: 1.0,
+            mode: CameraScaling::KeepVertical,
+        });
+        Self { main, ui, overlay }
     }
 }
 
@@ -302,28 +515,74 @@ pub enum GameScene {
     #[default]
     Menu,
     Ingame,
+    StageSelect,
+    Credits,
+    Statistics,
+}
+
+impl GameScene {
+    /// The transition used when switching to this scene.
+    pub fn transition(&self) -> Transition {
+        match self {
+            GameScene::Menu => Transition::Fade(Duration::from_millis(800)),
+            GameScene::Ingame => Transition::Dissolve(Duration::from_millis(600)),
+            GameScene::StageSelect | GameScene::Credits | GameScene::Statistics => {
+                Transition::Fade(Duration::from_millis(400))
+            }
+        }
+    }
+}
+
+/// Overlays that help debugging the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugView {
+    Colliders,
+    Performance,
+    FrameLog,
 }
 
 pub enum Scene {
     Menu(main_menu::MainMenu),
     Ingame(game_loop::Loop),
+    StageSelect(stage_select::StageSelect),
+    Credits(credits::Credits),
+    Statistics(statistics::Statistics),
 }
 
 impl Scene {
+    pub fn kind(&self) -> GameScene {
+        match self {
+            Self::Menu(_) => GameScene::Menu,
+            Self::Ingame(_) => GameScene::Ingame,
+            Self::StageSelect(_) => GameScene::StageSelect,
+            Self::Credits(_) => GameScene::Credits,
+            Self::Statistics(_) => GameScene::Statistics,
+        }
+    }
+
     pub fn update(&mut self) -> Result<Option<Message>> {
         match self {
             Self::Menu(menu) => menu.update(),
             Self::Ingame(game_loop) => game_loop.update(),
+            Self::StageSelect(stage_select) => stage_select.update(),
+            Self::Credits(credits) => credits.update(),
+            Self::Statistics(statistics) => statistics.update(),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Message {
     Exit,
     ChangeLevel(usize),
+    /// Starts the ingame scene playing the given run.
+    Play(game_loop::Run),
     ShowSettings(bool),
     SwitchScene(GameScene),
+    SwitchSceneWith(GameScene, Transition),
+    ToggleDebug(DebugView),
+    /// Opens or closes the stage editor.
+    ToggleEditor,
     ApplySettings(GameSettings),
 }
 
@@ -0,0 +1,266 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use let_engine::egui::{self, Color32, RichText, Stroke};
+
+use super::game_loop::GameState;
+
+/// Amount of frames the statistics are calculated over.
+const WINDOW: usize = 300;
+
+/// Parts of `Loop::update` that get timed separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Paddle,
+    Level,
+    Projectiles,
+    Enemies,
+    Particles,
+    Scenery,
+}
+
+impl Section {
+    pub const ALL: [Section; 6] = [
+        Section::Paddle,
+        Section::Level,
+        Section::Projectiles,
+        Section::Enemies,
+        Section::Particles,
+        Section::Scenery,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Paddle => "paddle",
+            Section::Level => "level",
+            Section::Projectiles => "projectiles",
+            Section::Enemies => "enemies",
+            Section::Particles => "particles",
+            Section::Scenery => "scenery",
+        }
+    }
+}
+
+/// Measures the time between laps and adds it to the given section.
+#[derive(Clone, Copy, Debug)]
+pub struct SectionTimer {
+    last: Instant,
+    times: [Duration; Section::ALL.len()],
+}
+
+impl SectionTimer {
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+            times: Default::default(),
+        }
+    }
+
+    /// Adds the time since the last lap to `section`.
+    pub fn lap(&mut self, section: Section) {
+        let now = Instant::now();
+        self.times[section as usize] += now - self.last;
+        self.last = now;
+    }
+
+    pub fn times(&self) -> [Duration; Section::ALL.len()] {
+        self.times
+    }
+}
+
+/// What a scene reports about its last update.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameReport {
+    pub sections: [Duration; Section::ALL.len()],
+    pub enemies: usize,
+    pub projectiles: usize,
+    pub particles: usize,
+}
+
+/// Frame time statistics in milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    /// The frame time the slowest 1% of frames take.
+    pub one_percent_low: f32,
+}
+
+impl FrameStats {
+    pub fn from_frame_times<'a>(frame_times: impl IntoIterator<Item = &'a f32>) -> Self {
+        let mut sorted: Vec<f32> = frame_times.into_iter().copied().collect();
+        if sorted.is_empty() {
+            return Self::default();
+        }
+        sorted.sort_by(f32::total_cmp);
+
+        let len = sorted.len();
+        Self {
+            min: sorted[0],
+            avg: sorted.iter().sum::<f32>() / len as f32,
+            max: sorted[len - 1],
+            one_percent_low: sorted[(len * 99 / 100).min(len - 1)],
+        }
+    }
+}
+
+/// A heads up display showing detailed performance statistics.
+pub struct PerfHud {
+    active: bool,
+    last_frame: Instant,
+    frame_times: VecDeque<f32>,
+    report: FrameReport,
+    log: Option<BufWriter<File>>,
+    log_start: Instant,
+}
+
+impl PerfHud {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            last_frame: Instant::now(),
+            frame_times: VecDeque::with_capacity(WINDOW),
+            report: FrameReport::default(),
+            log: None,
+            log_start: Instant::now(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+    }
+
+    /// Starts logging frame stats to a CSV file returning its path, or stops logging if it already does.
+    pub fn toggle_logging(&mut self) -> Result<Option<PathBuf>> {
+        if let Some(mut log) = self.log.take() {
+            log.flush()?;
+            return Ok(None);
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = GameState::data_dir()?.join(format!("frame-stats-{timestamp}.csv"));
+
+        let mut log = BufWriter::new(File::create(&path)?);
+        write!(log, "time,frame_ms,enemies,projectiles,particles")?;
+        for section in Section::ALL {
+            write!(log, ",{}_ms", section.name())?;
+        }
+        writeln!(log)?;
+
+        self.log = Some(log);
+        self.log_start = Instant::now();
+        Ok(Some(path))
+    }
+
+    /// Records the frame that just finished.
+    pub fn record(&mut self, report: FrameReport) -> Result<()> {
+        let now = Instant::now();
+        let frame_time = (now - self.last_frame).as_secs_f32() * 1000.0;
+        self.last_frame = now;
+        self.report = report;
+
+        if self.frame_times.len() == WINDOW {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+
+        if let Some(log) = self.log.as_mut() {
+            write!(
+                log,
+                "{:.4},{frame_time:.4},{},{},{}",
+                (now - self.log_start).as_secs_f32(),
+                report.enemies,
+                report.projectiles,
+                report.particles,
+            )?;
+            for time in report.sections {
+                write!(log, ",{:.4}", time.as_secs_f32() * 1000.0)?;
+            }
+            writeln!(log)?;
+        }
+        Ok(())
+    }
+
+    pub fn update(&mut self, context: &egui::Context) {
+        if !self.active {
+            return;
+        }
+        let stats = FrameStats::from_frame_times(&self.frame_times);
+
+        egui::Window::new("Performance")
+            .resizable(false)
+            .default_pos(egui::pos2(10.0, 40.0))
+            .show(context, |ui| {
+                self.frame_graph(ui, &stats);
+                let text = format!(
+                    "frame time over {} frames\n  min {:.2}ms\n  avg {:.2}ms ({:.0} fps)\n  max {:.2}ms\n  1% low {:.2}ms ({:.0} fps)",
+                    self.frame_times.len(),
+                    stats.min,
+                    stats.avg,
+                    1000.0 / stats.avg,
+                    stats.max,
+                    stats.one_percent_low,
+                    1000.0 / stats.one_percent_low,
+                );
+                ui.label(RichText::new(text).monospace());
+
+                let mut text = format!(
+                    "enemies: {}\nprojectiles: {}\nparticles: {}\n\nupdate sections",
+                    self.report.enemies,
+                    self.report.projectiles,
+                    self.report.particles,
+                );
+                for (section, time) in Section::ALL.iter().zip(self.report.sections) {
+                    text.push_str(&format!(
+                        "\n  {:<12}{:.3}ms",
+                        section.name(),
+                        time.as_secs_f32() * 1000.0
+                    ));
+                }
+                if self.log.is_some() {
+                    text.push_str("\n\nlogging to csv");
+                }
+                ui.label(RichText::new(text).monospace());
+            });
+    }
+
+    /// Draws the frame times of the window as a line graph.
+    fn frame_graph(&self, ui: &mut egui::Ui, stats: &FrameStats) {
+        let (response, painter) =
+            ui.allocate_painter(egui::vec2(WINDOW as f32, 80.0), egui::Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, 0.0, Color32::from_black_alpha(150));
+
+        // Scale the graph so that 60 fps is always visible.
+        let top = stats.max.max(1000.0 / 60.0) * 1.1;
+        let y = |frame_time: f32| rect.bottom() - frame_time / top * rect.height();
+
+        painter.hline(
+            rect.x_range(),
+            y(1000.0 / 60.0),
+            Stroke::new(1.0, Color32::DARK_GRAY),
+        );
+        let points = self
+            .frame_times
+            .iter()
+            .enumerate()
+            .map(|(i, frame_time)| egui::pos2(rect.left() + i as f32, y(*frame_time)))
+            .collect();
+        painter.add(egui::Shape::line(
+            points,
+            Stroke::new(1.0, Color32::LIGHT_GREEN),
+        ));
+    }
+}
+
+impl Default for PerfHud {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -43,4 +43,21 @@ impl Sounds {
             target_destroy,
         })
     }
+
+    /// Pauses or resumes every sound, so they stand still together with the game.
+    pub fn set_paused(&self, paused: bool) {
+        for sound in [
+            &self.critical,
+            &self.damage,
+            &self.square_hit,
+            &self.target_hit,
+            &self.target_destroy,
+        ] {
+            let _ = if paused {
+                sound.pause()
+            } else {
+                sound.resume()
+            };
+        }
+    }
 }
@@ -0,0 +1,176 @@
+use crate::objects::button::{Button, Parent};
+
+use super::{
+    accessibility,
+    game_loop::{GameState, Run},
+    load_material, locale,
+    main_menu::{back_button, backdrop, scene_title},
+    stages::STAGES,
+    GameScene, Layers, Message,
+};
+use anyhow::Result;
+use let_engine::prelude::*;
+
+/// Lists all stages letting the player replay the unlocked ones.
+pub struct StageSelect {
+    state: GameState,
+    backdrop: Object,
+    title: Label<Object>,
+    stages: Vec<(u32, Button)>,
+    endless_button: Button,
+    difficulty_button: Button,
+    back_button: Button,
+}
+
+impl StageSelect {
+    pub fn new(layers: &Layers) -> Result<Self> {
+        let state = GameState::load_or_init()?;
+
+        let backdrop = backdrop(layers)?;
+        let title = scene_title(layers, "scene.stages")?;
+
+        let button_material = load_material(&asset("textures/ui/button.png")?, 1);
+        let mut stages = vec![];
+        for stage in 0..STAGES.len() {
+            let unlocked = stage as u32 <= state.stage;
+            let text = stage_text(&state, stage as u32);
+            let mut button = Button::new(
+                Parent::Layer(&layers.ui),
+                button_material.clone(),
+                Some(
+                    LabelCreateInfo::default()
+                        .text(text)
+                        .scale(accessibility::text_size(vec2(40.0, 40.0)))
+                        .align(Direction::Center),
+                ),
+                vec2(0.0, -0.3 + stage as f32 * 0.25),
+            )?;
+            button.set_enabled(unlocked);
+            stages.push((stage as u32, button));
+        }
+
+        // Endless mode unlocks after beating every stage.
+        let mut endless_button = Button::new(
+            Parent::Layer(&layers.ui),
+            button_material.clone(),
+            Some(
+                LabelCreateInfo::default()
+                    .text(endless_text(&state))
+                    .scale(accessibility::text_size(vec2(40.0, 40.0)))
+                    .align(Direction::Center),
+            ),
+            vec2(0.0, -0.3 + STAGES.len() as f32 * 0.25),
+        )?;
+        endless_button.set_enabled(state.stage as usize >= STAGES.len());
+
+        let difficulty_button = Button::new(
+            Parent::Layer(&layers.ui),
+            button_material,
+            Some(
+                LabelCreateInfo::default()
+                    .text(locale::text(state.difficulty.key()))
+                    .scale(accessibility::text_size(vec2(45.0, 45.0)))
+                    .align(Direction::Center),
+            ),
+            vec2(0.0, 0.8),
+        )?;
+
+        let back_button = back_button(layers)?;
+
+        if let Some(window) = SETTINGS.window() {
+            window.set_cursor_visible(true);
+        }
+
+        Ok(Self {
+            state,
+            backdrop,
+            title,
+            stages,
+            endless_button,
+            difficulty_button,
+            back_button,
+        })
+    }
+
+    pub fn unload(self) {
+        let _ = self.backdrop.remove();
+        let _ = self.title.object.remove();
+        for (_, button) in self.stages {
+            button.remove();
+        }
+        self.endless_button.remove();
+        self.difficulty_button.remove();
+        self.back_button.remove();
+    }
+
+    pub fn update(&mut self) -> Result<Option<Message>> {
+        let mut message = None;
+
+        for (stage, button) in self.stages.iter_mut() {
+            button.on_release(|| message = Some(Message::Play(Run::Stage(*stage))));
+        }
+        self.endless_button
+            .on_release(|| message = Some(Message::Play(Run::Endless(rand::random()))));
+        self.back_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Menu)));
+
+        let mut cycle = false;
+        self.difficulty_button.on_release(|| cycle = true);
+        if cycle {
+            self.cycle_difficulty()?;
+        }
+
+        self.title.sync();
+        self.backdrop.sync()?;
+        Ok(message)
+    }
+
+    /// Switches to the next difficulty and saves it as the one new runs start with.
+    fn cycle_difficulty(&mut self) -> Result<()> {
+        self.state.difficulty = self.state.difficulty.next();
+        self.state.save()?;
+        if let Some(label) = self.difficulty_button.text.as_mut() {
+            label.update_text(locale::text(self.state.difficulty.key()));
+        }
+        // Best scores are kept per difficulty.
+        for (stage, button) in self.stages.iter_mut() {
+            if let Some(label) = button.text.as_mut() {
+                label.update_text(stage_text(&self.state, *stage));
+            }
+        }
+        if let Some(label) = self.endless_button.text.as_mut() {
+            label.update_text(endless_text(&self.state));
+        }
+        Ok(())
+    }
+}
+
+/// The best endless run on the current difficulty or a lock if it is not unlocked yet.
+fn endless_text(state: &GameState) -> String {
+    if state.stage as usize >= STAGES.len() {
+        let best = state
+            .endless_records
+            .get(&state.difficulty)
+            .map(|record| record.score)
+            .unwrap_or(0);
+        locale::text_with("stages.endless", &[("best", best.to_string())])
+    } else {
+        locale::text("stages.locked")
+    }
+}
+
+/// The name and best score of a stage or a lock if it is not unlocked yet.
+fn stage_text(state: &GameState, stage: u32) -> String {
+    if stage <= state.stage {
+        let name = STAGES[stage as usize].to_lowercase();
+        locale::text_with(
+            "stages.best",
+            &[
+                ("name", locale::text(&format!("stage.{name}"))),
+                ("best", state.best_score(stage).to_string()),
+            ],
+        )
+    } else {
+        locale::text("stages.locked")
+    }
+}
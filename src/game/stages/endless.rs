@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use let_engine::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    game::{
+        game_loop::{Level, LevelMessage as Msg},
+        locale, Layers,
+    },
+    objects::{enemies::EnemyType, titles::TitleCard},
+};
+
+/// Enemies endless mode picks from with the first wave they appear in and how often they get picked.
+///
+/// Only enemies that can be spawned belong here.
+const ROSTER: [(EnemyType, u32, u32); 1] = [(EnemyType::Target, 1, 1)];
+
+/// The first wave ending with a formation.
+const FORMATION_WAVE: u32 = 4;
+const FORMATIONS: [&str; 3] = ["column", "v", "ring"];
+/// Entry and idle paths formations pick from.
+const MOVEMENTS: [(&str, &str); 3] = [
+    ("enter_right", "bob"),
+    ("enter_top", "sweep"),
+    ("enter_swoop", "orbit"),
+];
+
+/// Generates the waves of an endless run.
+///
+/// The same seed always generates the same waves.
+pub struct WaveGenerator {
+    rng: StdRng,
+    wave: u32,
+}
+
+impl WaveGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            wave: 0,
+        }
+    }
+
+    /// The wave that was generated last.
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Generates the events of the next wave, each one denser than the last.
+    pub fn next_wave(&mut self) -> Vec<Msg> {
+        self.wave += 1;
+        let wave = self.wave;
+
+        let mut events = vec![
+            Msg::ChangeEnemyLimit(2 + wave / 3),
+            Msg::ChangeWaitingTime(Duration::from_secs(2)),
+            Msg::ShowTitle(TitleCard::new(
+                locale::text_with("endless.wave", &[("wave", wave.to_string())]),
+                Color::from_rgb(0.9, 0.3, 0.1),
+                vec2(70.0, 70.0),
+            )),
+        ];
+
+        let spawn_delay = (3.0 - wave as f32 * 0.15).max(0.5);
+        let count = 2 + wave + self.rng.gen_range(0..=wave / 2);
+        for _ in 0..count {
+            // Vary the pace a little so waves do not feel like a metronome.
+            let delay = spawn_delay * self.rng.gen_range(0.6..1.4);
+            events.push(Msg::ChangeWaitingTime(Duration::from_secs_f32(delay)));
+            events.push(Msg::SpawnEnemy(self.pick_enemy()));
+        }
+        // Later waves end with a group flying in together.
+        if wave >= FORMATION_WAVE {
+            let formation = FORMATIONS[self.rng.gen_range(0..FORMATIONS.len())];
+            let (entry, idle) = MOVEMENTS[self.rng.gen_range(0..MOVEMENTS.len())];
+            events.push(Msg::SpawnFormation {
+                enemy: self.pick_enemy(),
+                formation: formation.to_string(),
+                count: (3 + wave as usize / 5).min(6),
+                anchor: vec2(self.rng.gen_range(1.8..2.6), self.rng.gen_range(-0.3..0.3)),
+                entry: entry.to_string(),
+                idle: idle.to_string(),
+            });
+        }
+        events
+    }
+
+    /// Picks a random enemy out of the ones unlocked in the current wave.
+    fn pick_enemy(&mut self) -> EnemyType {
+        let unlocked = ROSTER
+            .iter()
+            .filter(|(_, first_wave, _)| *first_wave <= self.wave);
+        let total: u32 = unlocked.clone().map(|(_, _, weight)| weight).sum();
+        let mut pick = self.rng.gen_range(0..total.max(1));
+        for (enemy, _, weight) in unlocked {
+            if pick < *weight {
+                return *enemy;
+            }
+            pick -= weight;
+        }
+        ROSTER[0].0
+    }
+}
+
+pub fn endless(layers: &Layers, seed: u64) -> Result<Level> {
+    let level = Level::new(layers, 2, Duration::from_secs(2), vec![])?;
+    Ok(level.with_generator(WaveGenerator::new(seed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waves(seed: u64, amount: usize) -> Vec<Vec<Msg>> {
+        let mut generator = WaveGenerator::new(seed);
+        (0..amount).map(|_| generator.next_wave()).collect()
+    }
+
+    /// The amount of enemies the wave spawns.
+    fn enemies(wave: &[Msg]) -> usize {
+        wave.iter()
+            .map(|message| match message {
+                Msg::SpawnEnemy(_) => 1,
+                Msg::SpawnFormation { count, .. } => *count,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    fn enemy_limit(wave: &[Msg]) -> u32 {
+        wave.iter()
+            .find_map(|message| match message {
+                Msg::ChangeEnemyLimit(limit) => Some(*limit),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_waves() {
+        assert_eq!(
+            format!("{:?}", waves(42, 10)),
+            format!("{:?}", waves(42, 10))
+        );
+    }
+
+    #[test]
+    fn different_seeds_differ() {
+        assert_ne!(format!("{:?}", waves(1, 10)), format!("{:?}", waves(2, 10)));
+    }
+
+    #[test]
+    fn waves_get_denser() {
+        for seed in 0..20 {
+            let waves = waves(seed, 12);
+            assert!(enemies(&waves[0]) < enemies(&waves[5]));
+            assert!(enemies(&waves[5]) < enemies(&waves[11]));
+            for pair in waves.windows(2) {
+                assert!(enemy_limit(&pair[0]) <= enemy_limit(&pair[1]));
+            }
+        }
+    }
+}
@@ -1,2 +1,122 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    game_loop::{self, GameState, Level, LevelMessage},
+    Layers,
+};
+
+pub use endless::endless;
+
+pub mod endless;
 pub mod part_one;
 pub mod part_three;
+
+/// Names of all stages in the order they get unlocked.
+pub const STAGES: [&str; 1] = ["Tutorial"];
+
+/// Everything needed to build the level of a stage.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Stage {
+    pub name: String,
+    pub enemy_limit: u32,
+    /// Time waited between events.
+    pub event_duration: Duration,
+    pub events: Vec<LevelMessage>,
+}
+
+impl Stage {
+    /// The stage as it is written in the game.
+    pub fn builtin(stage: u32) -> Option<Self> {
+        match stage {
+            0 => Some(part_one::tutorial()),
+            _ => None,
+        }
+    }
+
+    /// The stage saved by the stage editor or the built-in one if it was never edited.
+    pub fn load(stage: u32) -> Result<Option<Self>> {
+        let Some(name) = STAGES.get(stage as usize) else {
+            return Ok(None);
+        };
+        let path = Self::path(name)?;
+        if path.exists() {
+            let file = std::fs::read(path)?;
+            let stage: Self = ron::de::from_reader(file.as_slice())?;
+            stage.validate()?;
+            return Ok(Some(stage));
+        }
+        Ok(Self::builtin(stage))
+    }
+
+    /// Checks that the stage can be played without running into unknown names or labels.
+    pub fn validate(&self) -> Result<()> {
+        game_loop::validate(&self.events)
+    }
+
+    /// Saves the stage so it gets loaded instead of the built-in one, returning the path.
+    pub fn save(&self) -> Result<PathBuf> {
+        self.validate()?;
+        let path = Self::path(&self.name)?;
+        let data = ron::ser::to_string_pretty(&self, ron::ser::PrettyConfig::new())?;
+        std::fs::write(&path, data)?;
+        Ok(path)
+    }
+
+    /// Deletes the saved stage file, so the built-in stage gets loaded again.
+    pub fn reset(&self) -> Result<()> {
+        let path = Self::path(&self.name)?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn path(name: &str) -> Result<PathBuf> {
+        let dir = GameState::data_dir()?.join("stages");
+        if !dir.exists() {
+            std::fs::create_dir_all(&dir)?;
+        }
+        Ok(dir.join(format!("{}.ron", name.to_lowercase())))
+    }
+
+    pub fn level(&self, layers: &Layers) -> Result<Level> {
+        Level::new(
+            layers,
+            self.enemy_limit,
+            self.event_duration,
+            self.events.clone(),
+        )
+    }
+}
+
+/// Loads the level of the given stage if it exists.
+pub fn load(stage: u32, layers: &Layers) -> Result<Option<Level>> {
+    Stage::load(stage)?
+        .map(|stage| stage.level(layers))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::enemies::EnemyType;
+
+    #[test]
+    fn builtin_stages_are_valid() {
+        for stage in 0..STAGES.len() as u32 {
+            Stage::builtin(stage).unwrap().validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_unspawnable_enemies() {
+        let mut stage = Stage::builtin(0).unwrap();
+        stage
+            .events
+            .push(LevelMessage::SpawnEnemy(EnemyType::Dragon));
+        assert!(stage.validate().is_err());
+    }
+}
@@ -1,64 +1,51 @@
-use std::{collections::VecDeque, time::Duration};
+use std::time::Duration;
 
-use anyhow::Result;
 use let_engine::prelude::*;
 
 use crate::{
-    game::{
-        game_loop::{Level, LevelMessage as Msg},
-        Layers,
+    game::game_loop::LevelMessage as Msg,
+    objects::{
+        enemies::{spawn::Spawn, EnemyType},
+        titles::TitleCard,
     },
-    objects::enemies::EnemyType,
 };
 
-pub fn tutorial(layers: &Layers) -> Result<Level> {
+use super::Stage;
+
+/// The first stage. Its text is made of localization keys.
+pub fn tutorial() -> Stage {
     let color = Color::from_rgb(0.9, 0.3, 0.1);
+    let hint = |text: &str| Msg::ShowTitle(TitleCard::new(text, color, vec2(60.0, 60.0)));
 
-    let events = VecDeque::from([
-        Msg::ShowTitle {
-            color,
-            size: vec2(70.0, 70.0),
-            text: "Tutorial Stage".to_string(),
-        },
+    let events = vec![
+        Msg::ShowTitle(
+            TitleCard::new("tutorial.title", color, vec2(70.0, 70.0)).subtitle("tutorial.subtitle"),
+        ),
         Msg::ChangeWaitingTime(Duration::from_secs(4)),
-        Msg::ShowTitle {
-            color,
-            size: vec2(60.0, 60.0),
-            text: "Move your mouse up and down to control your paddle.".to_string(),
-        },
-        Msg::ShowTitle {
-            color,
-            size: vec2(60.0, 60.0),
-            text: "To move the arrow press the mouse buttons.".to_string(),
-        },
-        Msg::ShowTitle {
-            color,
-            size: vec2(60.0, 60.0),
-            text: "By touching the projectiles you send them to where the arrow is pointing."
-                .to_string(),
-        },
+        hint("tutorial.move"),
+        hint("tutorial.arrow"),
+        hint("tutorial.return"),
         Msg::SpawnEnemy(EnemyType::Target),
         Msg::ChangeWaitingTime(Duration::from_secs(5)),
-        Msg::ShowTitle {
-            color,
-            size: vec2(60.0, 60.0),
-            text: "Hit the enemies with their projectiles to damage them.".to_string(),
-        },
+        hint("tutorial.damage"),
         Msg::ChangeWaitingTime(Duration::from_secs(1)),
-        Msg::SpawnEnemy(EnemyType::Target),
-        Msg::SpawnEnemy(EnemyType::Target),
+        Msg::SpawnGroup(vec![
+            Spawn::new(EnemyType::Target).position(vec2(2.2, -0.4)),
+            Spawn::new(EnemyType::Target).position(vec2(2.2, 0.4)),
+        ]),
         Msg::ChangeWaitingTime(Duration::from_secs(6)),
-        Msg::ShowTitle {
-            color,
-            size: vec2(60.0, 60.0),
-            text: "You can return an extra hard projectile back by touching it with the side of your paddle.".to_string(),
-        },
+        hint("tutorial.hard_shot"),
         Msg::ChangeWaitingTime(Duration::from_secs(1)),
         Msg::SpawnEnemy(EnemyType::Target),
         Msg::SpawnEnemy(EnemyType::Target),
         Msg::ChangeWaitingTime(Duration::from_secs(5)),
         Msg::SpawnEnemy(EnemyType::Target),
-    ]);
+    ];
 
-    Level::new(layers, 2, Duration::from_secs(2), events)
+    Stage {
+        name: "Tutorial".to_string(),
+        enemy_limit: 2,
+        event_duration: Duration::from_secs(2),
+        events,
+    }
 }
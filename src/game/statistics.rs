@@ -0,0 +1,114 @@
+use crate::objects::button::Button;
+
+use super::{
+    accessibility,
+    game_loop::GameState,
+    locale,
+    main_menu::{back_button, backdrop, scene_title},
+    stages::STAGES,
+    GameScene, Layers, Message,
+};
+use anyhow::Result;
+use let_engine::prelude::*;
+
+/// Shows the totals of the save file.
+pub struct Statistics {
+    backdrop: Object,
+    title: Label<Object>,
+    names: Label<Object>,
+    values: Label<Object>,
+    back_button: Button,
+}
+
+impl Statistics {
+    pub fn new(layers: &Layers) -> Result<Self> {
+        let state = GameState::load_or_init()?;
+
+        let backdrop = backdrop(layers)?;
+        let title = scene_title(layers, "scene.statistics")?;
+
+        let play_time = state.play_time as u64;
+        let endless_wave = state
+            .endless_records
+            .get(&state.difficulty)
+            .map(|record| record.wave)
+            .unwrap_or(0);
+        let values = format!(
+            "{}\n\n{}h {:02}m {:02}s\n\n{}\n\n{}\n\n{}/{}\n\n{}\n\n{}",
+            state.kills,
+            play_time / 3600,
+            play_time / 60 % 60,
+            play_time % 60,
+            state.hard_shots,
+            state.score,
+            (state.stage as usize).min(STAGES.len()),
+            STAGES.len(),
+            locale::text(state.difficulty.key()),
+            endless_wave,
+        );
+
+        // Names on the left and values on the right side of the same box.
+        let column = |text: &str, align: Direction| {
+            Label::new(
+                locale::display_font(),
+                LabelCreateInfo::default()
+                    .text(text)
+                    .align(align)
+                    .transform(Transform::default().position(vec2(0.0, 0.1)))
+                    .appearance(
+                        Appearance::default().transform(Transform::default().size(vec2(0.8, 0.6))),
+                    )
+                    .scale(accessibility::text_size(vec2(47.0, 47.0))),
+            )
+            .init(&layers.ui)
+        };
+        let names = [
+            "statistics.kills",
+            "statistics.play_time",
+            "statistics.hard_shots",
+            "statistics.score",
+            "statistics.stages",
+            "statistics.difficulty",
+            "statistics.endless_wave",
+        ]
+        .map(locale::text)
+        .join("\n\n");
+        let names = column(&names, Direction::W)?;
+        let values = column(&values, Direction::E)?;
+
+        let back_button = back_button(layers)?;
+
+        if let Some(window) = SETTINGS.window() {
+            window.set_cursor_visible(true);
+        }
+
+        Ok(Self {
+            backdrop,
+            title,
+            names,
+            values,
+            back_button,
+        })
+    }
+
+    pub fn unload(self) {
+        let _ = self.backdrop.remove();
+        let _ = self.title.object.remove();
+        let _ = self.names.object.remove();
+        let _ = self.values.object.remove();
+        self.back_button.remove();
+    }
+
+    pub fn update(&mut self) -> Result<Option<Message>> {
+        let mut message = None;
+
+        self.back_button
+            .on_release(|| message = Some(Message::SwitchScene(GameScene::Menu)));
+
+        self.names.sync();
+        self.values.sync();
+        self.title.sync();
+        self.backdrop.sync()?;
+        Ok(message)
+    }
+}
@@ -17,6 +17,10 @@ pub static HEIGHT: f32 = 256.0;
 
 fn main() -> Result<()> {
     let settings = game::GameSettings::load().unwrap_or_default();
+    // A broken language file should not keep the game from starting.
+    let _ = game::locale::set_language(settings.language);
+    game::accessibility::set(settings.accessibility);
+    game::layout::set(settings.layout, settings.resolution);
 
     let tick_settings = TickSettingsBuilder::default()
         .update_physics(false)
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use let_engine::prelude::*;
+
+use crate::game::{
+    accessibility,
+    input::Action,
+    layout::{self, Anchor},
+    locale,
+};
+
+use super::paddle::Paddle;
+
+/// Distance from the paddle in which a parry reflects projectiles.
+pub const PARRY_RANGE: f32 = 0.35;
+/// Damage multiplier of parried projectiles.
+pub const PARRY_BONUS: f32 = 1.5;
+
+const DASH_SPEED: f32 = 3.0;
+const DASH_TIME: f32 = 0.12;
+const DASH_COOLDOWN: f32 = 2.0;
+const PARRY_TIME: f32 = 0.2;
+const PARRY_COOLDOWN: f32 = 3.0;
+/// Movement speed multiplier while focusing.
+const FOCUS_SPEED: f32 = 0.4;
+
+/// The active abilities of the paddle and their cooldown indicator.
+pub struct Abilities {
+    /// Seconds until the dash can be used again.
+    dash_cooldown: f32,
+    /// Seconds the current dash still moves the paddle.
+    dash_time: f32,
+    dash_direction: f32,
+    parry_cooldown: f32,
+    parry_time: f32,
+    focus: bool,
+
+    /// Shows the exact hitbox of the paddle while focusing or parrying.
+    hitbox: Object,
+    indicator: Label<Object>,
+}
+
+impl Abilities {
+    pub fn new(layer: &Arc<Layer>, paddle: &Paddle) -> Result<Self> {
+        let hitbox = NewObjectBuilder::default()
+            .appearance(
+                Appearance::new()
+                    .model(Some(Model::Square))
+                    .transform(Transform::default().size(paddle.hitbox()))
+                    .visible(false),
+            )
+            .build()?
+            .init_with_parent(&paddle.object)?;
+
+        let indicator = Label::new(
+            locale::font(),
+            LabelCreateInfo::default()
+                .align(Anchor::BottomLeft.align())
+                .scale(accessibility::text_size(vec2(35.0, 35.0)))
+                .appearance(
+                    Appearance::default()
+                        .transform(layout::label_transform(Vec2::ZERO))
+                        .color(Color::BLACK),
+                ),
+        )
+        .init(layer)?;
+
+        Ok(Self {
+            dash_cooldown: 0.0,
+            dash_time: 0.0,
+            dash_direction: 0.0,
+            parry_cooldown: 0.0,
+            parry_time: 0.0,
+            focus: false,
+            hitbox,
+            indicator,
+        })
+    }
+
+    /// Handles the press or release of an action key.
+    pub fn input(&mut self, action: Action, pressed: bool, paddle: &Paddle) {
+        match action {
+            Action::Dash if pressed && self.dash_cooldown <= 0.0 => {
+                // Dash the way the paddle is already moving.
+                self.dash_direction = if paddle.cursor.transform.position.y < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                self.dash_time = DASH_TIME;
+                self.dash_cooldown = DASH_COOLDOWN;
+            }
+            Action::Parry if pressed && self.parry_cooldown <= 0.0 => {
+                self.parry_time = PARRY_TIME;
+                self.parry_cooldown = PARRY_COOLDOWN;
+            }
+            Action::Focus => self.focus = pressed,
+            _ => (),
+        }
+    }
+
+    /// Whether the parry window is open right now.
+    pub fn parrying(&self) -> bool {
+        self.parry_time > 0.0
+    }
+
+    /// Counts down the cooldowns and applies the abilities to the paddle before it updates.
+    pub fn update(&mut self, paddle: &mut Paddle) -> Result<()> {
+        let delta_time = TIME.delta_time() as f32;
+        self.dash_cooldown = (self.dash_cooldown - delta_time).max(0.0);
+        self.parry_cooldown = (self.parry_cooldown - delta_time).max(0.0);
+        self.parry_time = (self.parry_time - delta_time).max(0.0);
+
+        if self.dash_time > 0.0 {
+            self.dash_time -= delta_time;
+            paddle.object.transform.position.y += self.dash_direction * DASH_SPEED * delta_time;
+        }
+
+        paddle.speed = if self.focus { FOCUS_SPEED } else { 1.0 };
+
+        self.hitbox
+            .appearance
+            .set_visible(self.focus || self.parrying());
+        self.hitbox.appearance.get_transform_mut().size = paddle.hitbox();
+        self.hitbox.appearance.set_color(if self.parrying() {
+            Color::from_rgba(1.0, 1.0, 0.4, 0.6)
+        } else {
+            Color::from_rgba(1.0, 0.2, 0.2, 0.5)
+        });
+        self.hitbox.sync()?;
+
+        let text = [
+            (Action::Dash, "ability.dash", self.dash_cooldown),
+            (Action::Parry, "ability.parry", self.parry_cooldown),
+        ]
+        .iter()
+        .map(|(action, key, cooldown)| {
+            let name = locale::text(key);
+            if *cooldown > 0.0 {
+                format!("{name} {cooldown:.1}s")
+            } else {
+                format!("{name} [{}]", action.key_name())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+        if self.indicator.text != text {
+            self.indicator.update_text(text);
+        }
+        self.indicator
+            .object
+            .appearance
+            .set_transform(layout::label_transform(Vec2::ZERO));
+        self.indicator.sync();
+        Ok(())
+    }
+
+    pub fn remove(self) {
+        let _ = self.hitbox.remove();
+        let _ = self.indicator.object.remove();
+    }
+}
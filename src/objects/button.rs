@@ -3,7 +3,15 @@ use std::sync::Arc;
 use anyhow::Result;
 use let_engine::prelude::*;
 
-use crate::{FONT_RAWR, HEIGHT};
+use crate::{
+    game::{
+        layout::{self, Anchor},
+        locale,
+    },
+    HEIGHT,
+};
+
+use super::tween::{Easing, RealClock, Tween};
 
 #[derive(Clone, Debug)]
 pub struct Button {
@@ -11,6 +19,11 @@ pub struct Button {
     pub text: Option<Label<Object>>,
     hovered: bool,
     pressed: bool,
+    /// Keeps the button at a point of the safe area instead of a fixed position.
+    anchor: Option<(Anchor, Vec2)>,
+    brightness: Tween<f32>,
+    // Buttons are used in the pause menu, so they have to animate in real time.
+    clock: RealClock,
 }
 
 pub enum Parent<'a> {
@@ -52,7 +65,7 @@ impl Button {
 
         let text = label_create_info.map(|mut label_create_info| {
             label_create_info.appearance.get_transform_mut().size = size;
-            Label::new(&FONT_RAWR, label_create_info)
+            Label::new(locale::display_font(), label_create_info)
                 .init_with_parent(&object)
                 .unwrap()
         });
@@ -62,9 +75,18 @@ impl Button {
             text,
             hovered: false,
             pressed: false,
+            anchor: None,
+            brightness: Tween::new(1.0, 1.0, 0.1).easing(Easing::QuadOut),
+            clock: RealClock::new(),
         })
     }
 
+    /// Places the button at the anchor moved by the offset, following it when the window resizes.
+    pub fn anchored(mut self, anchor: Anchor, offset: Vec2) -> Self {
+        self.anchor = Some((anchor, offset));
+        self
+    }
+
     pub fn set_enabled(&mut self, enabled: bool) {
         self.object.collider_mut().unwrap().set_enabled(enabled);
     }
@@ -77,6 +99,9 @@ impl Button {
     }
 
     fn update(&mut self) -> Option<ButtonReport> {
+        if let Some((anchor, offset)) = self.anchor {
+            self.object.transform.position = layout::position(anchor, offset);
+        }
         self.object.sync().unwrap();
         if let Some(text) = self.text.as_mut() {
             text.sync();
@@ -86,35 +111,42 @@ impl Button {
             layer.intersections_with_ray(INPUT.cursor_to_world(layer), vec2(0.0, 0.0), 0.0, true);
         let mut report = None;
 
-        if let Some(id) = intersections.first() {
-            if id != self.object.id() {
-                self.object
-                    .appearance
-                    .set_color(Color::from_rgb(1.0, 1.0, 1.0));
+        match intersections.first() {
+            Some(id) if id == self.object.id() => {
+                self.hovered = true;
+                let pressed = INPUT.mouse_down(&MouseButton::Left);
+                if !self.pressed && pressed {
+                    report = Some(ButtonReport::Pressed)
+                } else if self.pressed && !pressed {
+                    report = Some(ButtonReport::Released)
+                }
+                self.pressed = pressed;
+            }
+            _ => {
                 self.hovered = false;
                 self.pressed = false;
-                return None;
-            }
-            self.hovered = true;
-            self.object
-                .appearance
-                .set_color(Color::from_rgb(1.5, 1.5, 1.5));
-            let pressed = INPUT.mouse_down(&MouseButton::Left);
-            if !self.pressed && pressed {
-                report = Some(ButtonReport::Pressed)
-            } else if self.pressed && !pressed {
-                report = Some(ButtonReport::Released)
-            }
-            self.pressed = pressed;
-            if pressed {
-                self.object
-                    .appearance
-                    .set_color(Color::from_rgb(0.5, 0.5, 0.5));
             }
         }
+        self.animate();
         report
     }
 
+    /// Fades the brightness towards the one of the current button state.
+    fn animate(&mut self) {
+        let target = match (self.hovered, self.pressed) {
+            (_, true) => 0.5,
+            (true, false) => 1.5,
+            (false, false) => 1.0,
+        };
+        if self.brightness.target() != target {
+            self.brightness.retarget(target);
+        }
+        let brightness = self.brightness.advance(self.clock.delta());
+        self.object
+            .appearance
+            .set_color(Color::from_rgb(brightness, brightness, brightness));
+    }
+
     #[allow(dead_code)]
     pub fn on_press(&mut self, action: impl FnOnce()) {
         if let Some(ButtonReport::Pressed) = self.update() {
@@ -0,0 +1,36 @@
+//! Game time that stands still while the game is paused.
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use let_engine::prelude::*;
+
+/// Seconds of game time passed since the start stored as the bits of a `f64`.
+static ELAPSED: AtomicU64 = AtomicU64::new(0);
+
+/// Advances the game time by the scaled delta time. Has to be called once every frame.
+pub fn tick() {
+    let elapsed = now() + TIME.delta_time();
+    ELAPSED.store(elapsed.to_bits(), Ordering::Relaxed);
+}
+
+/// Seconds of game time passed since the start.
+pub fn now() -> f64 {
+    f64::from_bits(ELAPSED.load(Ordering::Relaxed))
+}
+
+/// A point in game time working like `Instant`, but not advancing while paused.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct GameInstant(f64);
+
+impl GameInstant {
+    pub fn now() -> Self {
+        Self(now())
+    }
+
+    /// Game time passed since this instant.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.0).max(0.0))
+    }
+}
@@ -0,0 +1,329 @@
+//! Overlay drawing colliders and object state to debug collisions.
+use std::{f32::consts::FRAC_1_SQRT_2, sync::Arc};
+
+use anyhow::Result;
+use let_engine::prelude::*;
+
+use crate::{game::Layers, FONT_STINGRAY};
+
+use super::{enemies::Enemy, paddle::Paddle, projectiles::Projectile};
+
+/// The shape of a collider as it was built using `Shape::square` or `Shape::circle`.
+#[derive(Clone, Copy, Debug)]
+pub enum ColliderShape {
+    /// Half extents of a square.
+    Square(Vec2),
+    /// Radius of a circle.
+    Circle(f32),
+}
+
+/// Everything the debug overlay needs to know about a single object.
+#[derive(Clone, Debug)]
+pub struct DebugInfo {
+    /// The ID reported by intersection tests, if the object has a collider.
+    pub id: Option<usize>,
+    pub layer: Arc<Layer>,
+    pub position: Vec2,
+    pub shape: ColliderShape,
+    pub velocity: Option<Vec2>,
+}
+
+const SQUARE_OUTLINE: ([Vertex; 4], [u32; 8]) = (
+    [
+        vert(-1.0, -1.0),
+        vert(1.0, -1.0),
+        vert(1.0, 1.0),
+        vert(-1.0, 1.0),
+    ],
+    [0, 1, 1, 2, 2, 3, 3, 0],
+);
+
+pub(super) const CIRCLE_OUTLINE: ([Vertex; 16], [u32; 32]) = (
+    [
+        vert(1.0, 0.0),
+        vert(0.92388, 0.38268),
+        vert(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        vert(0.38268, 0.92388),
+        vert(0.0, 1.0),
+        vert(-0.38268, 0.92388),
+        vert(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        vert(-0.92388, 0.38268),
+        vert(-1.0, 0.0),
+        vert(-0.92388, -0.38268),
+        vert(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        vert(-0.38268, -0.92388),
+        vert(0.0, -1.0),
+        vert(0.38268, -0.92388),
+        vert(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        vert(0.92388, -0.38268),
+    ],
+    [
+        0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+        14, 14, 15, 15, 0,
+    ],
+);
+
+pub(super) const LINE: ([Vertex; 2], [u32; 2]) = ([vert(0.0, 0.0), vert(1.0, 0.0)], [0, 1]);
+
+/// How far ahead velocity lines are drawn in seconds.
+const VELOCITY_PREVIEW: f32 = 0.25;
+
+fn collider_color() -> Color {
+    Color::from_rgba(0.0, 1.0, 0.2, 0.8)
+}
+fn friendly_color() -> Color {
+    Color::from_rgba(0.2, 0.6, 1.0, 0.8)
+}
+fn velocity_color() -> Color {
+    Color::from_rgba(1.0, 0.9, 0.0, 0.8)
+}
+fn arrow_color() -> Color {
+    Color::from_rgba(1.0, 0.0, 1.0, 0.8)
+}
+
+/// A reusable set of line objects of the same model.
+pub(super) struct Pool {
+    layer: Arc<Layer>,
+    appearance: Appearance,
+    objects: Vec<Object>,
+    used: usize,
+}
+
+impl Pool {
+    pub(super) fn new(
+        layer: &Arc<Layer>,
+        vertices: &'static [Vertex],
+        indices: &'static [u32],
+    ) -> Result<Self> {
+        let model = Model::Custom(ModelData::new(Data::Fixed { vertices, indices })?);
+        let appearance = Appearance::new().model(Some(model)).material(
+            Material::new(
+                MaterialSettingsBuilder::default()
+                    .topology(Topology::LineList)
+                    .line_width(2.0)
+                    .build()?,
+            )
+            .ok(),
+        );
+        Ok(Self {
+            layer: layer.clone(),
+            appearance,
+            objects: vec![],
+            used: 0,
+        })
+    }
+
+    /// Draws the next object of this pool, creating a new one if all are in use.
+    pub(super) fn draw(&mut self, transform: Transform, size: Vec2, color: Color) -> Result<()> {
+        if self.used == self.objects.len() {
+            let object = NewObjectBuilder::default()
+                .appearance(self.appearance.clone())
+                .build()?
+                .init(&self.layer)?;
+            self.objects.push(object);
+        }
+        let object = &mut self.objects[self.used];
+        object.transform = transform;
+        object.appearance.get_transform_mut().size = size;
+        object.appearance.set_color(color);
+        object.appearance.set_visible(true);
+        object.sync()?;
+        self.used += 1;
+        Ok(())
+    }
+
+    /// Hides all objects that were not drawn this frame.
+    pub(super) fn finish(&mut self) -> Result<()> {
+        for object in &mut self.objects[self.used..] {
+            if object.appearance.get_visible() {
+                object.appearance.set_visible(false);
+                object.sync()?;
+            }
+        }
+        self.used = 0;
+        Ok(())
+    }
+
+    pub(super) fn remove(self) {
+        for object in self.objects {
+            let _ = object.remove();
+        }
+    }
+}
+
+pub struct DebugOverlay {
+    layers: Layers,
+    squares: Pool,
+    circles: Pool,
+    lines: Pool,
+    ids: Vec<Label<Object>>,
+    stats: Label<Object>,
+}
+
+impl DebugOverlay {
+    pub fn new(layers: &Layers) -> Result<Self> {
+        let stats = Label::new(
+            &FONT_STINGRAY,
+            LabelCreateInfo::default()
+                .scale(vec2(20.0, 20.0))
+                .align(Direction::Ne),
+        )
+        .init(&layers.ui)?;
+
+        Ok(Self {
+            layers: layers.clone(),
+            squares: Pool::new(&layers.main, &SQUARE_OUTLINE.0, &SQUARE_OUTLINE.1)?,
+            circles: Pool::new(&layers.main, &CIRCLE_OUTLINE.0, &CIRCLE_OUTLINE.1)?,
+            lines: Pool::new(&layers.main, &LINE.0, &LINE.1)?,
+            ids: vec![],
+            stats,
+        })
+    }
+
+    pub fn remove(self) {
+        self.squares.remove();
+        self.circles.remove();
+        self.lines.remove();
+        for label in self.ids {
+            let _ = label.object.remove();
+        }
+        let _ = self.stats.object.remove();
+    }
+
+    pub fn update(
+        &mut self,
+        paddle: &Paddle,
+        enemies: &[Box<dyn Enemy>],
+        projectiles: &[Box<dyn Projectile>],
+    ) -> Result<()> {
+        let paddle_info = paddle.debug_info();
+        self.draw_collider(&paddle_info, collider_color())?;
+        self.draw_line(
+            paddle_info.position,
+            paddle.rebound_direction(),
+            arrow_color(),
+        )?;
+
+        let enemies: Vec<DebugInfo> = enemies.iter().map(|enemy| enemy.debug_info()).collect();
+        for enemy in &enemies {
+            self.draw_collider(enemy, collider_color())?;
+        }
+
+        let mut friendly = 0;
+        let mut projectile_infos = Vec::with_capacity(projectiles.len());
+        for projectile in projectiles {
+            let info = projectile.debug_info();
+            if projectile.friendly() {
+                friendly += 1;
+                self.draw_collider(&info, friendly_color())?;
+            } else {
+                self.draw_collider(&info, collider_color())?;
+            }
+            projectile_infos.push(info);
+        }
+
+        self.squares.finish()?;
+        self.circles.finish()?;
+        self.lines.finish()?;
+
+        self.update_ids(&enemies)?;
+        self.update_stats(&enemies, &projectile_infos, friendly)
+    }
+
+    fn draw_collider(&mut self, info: &DebugInfo, color: Color) -> Result<()> {
+        let transform = Transform::default().position(info.position);
+        match info.shape {
+            ColliderShape::Square(half_extents) => {
+                self.squares.draw(transform, half_extents, color)?
+            }
+            ColliderShape::Circle(radius) => {
+                self.circles.draw(transform, Vec2::splat(radius), color)?
+            }
+        }
+        if let Some(velocity) = info.velocity {
+            self.draw_line(info.position, velocity * VELOCITY_PREVIEW, velocity_color())?;
+        }
+        Ok(())
+    }
+
+    fn draw_line(&mut self, start: Vec2, vector: Vec2, color: Color) -> Result<()> {
+        let transform = Transform::default()
+            .position(start)
+            .rotation(vector.y.atan2(vector.x));
+        self.lines
+            .draw(transform, Vec2::splat(vector.length()), color)
+    }
+
+    /// Puts a label with the collider ID above every enemy.
+    fn update_ids(&mut self, enemies: &[DebugInfo]) -> Result<()> {
+        while self.ids.len() < enemies.len() {
+            let label = Label::new(
+                &FONT_STINGRAY,
+                LabelCreateInfo::default()
+                    .scale(vec2(20.0, 20.0))
+                    .align(Direction::S)
+                    .appearance(
+                        Appearance::default()
+                            .transform(Transform::default().size(vec2(0.3, 0.1)))
+                            .color(collider_color()),
+                    ),
+            )
+            .init(&self.layers.main)?;
+            self.ids.push(label);
+        }
+
+        for (label, enemy) in self.ids.iter_mut().zip(enemies) {
+            let top = match enemy.shape {
+                ColliderShape::Square(half_extents) => half_extents.y,
+                ColliderShape::Circle(radius) => radius,
+            };
+            label.object.transform.position = enemy.position - vec2(0.0, top + 0.1);
+            label.object.appearance.set_visible(true);
+            label.update_text(match enemy.id {
+                Some(id) => id.to_string(),
+                None => "-".to_string(),
+            });
+            label.object.sync()?;
+        }
+        for label in &mut self.ids[enemies.len()..] {
+            if label.object.appearance.get_visible() {
+                label.object.appearance.set_visible(false);
+                label.object.sync()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows the amount of live enemies and projectiles on each layer.
+    fn update_stats(
+        &mut self,
+        enemies: &[DebugInfo],
+        projectiles: &[DebugInfo],
+        friendly: usize,
+    ) -> Result<()> {
+        let count = |infos: &[DebugInfo], layer: &Arc<Layer>| {
+            infos
+                .iter()
+                .filter(|info| Arc::ptr_eq(&info.layer, layer))
+                .count()
+        };
+        let mut text = String::from("colliders");
+        for (name, layer) in [("main", &self.layers.main), ("ui", &self.layers.ui)] {
+            text.push_str(&format!(
+                "\n{name}: {} enemies, {} projectiles",
+                count(enemies, layer),
+                count(projectiles, layer),
+            ));
+        }
+        text.push_str(&format!("\nfriendly projectiles: {friendly}"));
+
+        if let Some(window) = SETTINGS.window() {
+            self.stats.object.appearance.set_transform(
+                Transform::default().size(CameraScaling::KeepVertical.scale(window.inner_size())),
+            );
+        }
+        self.stats.update_text(text);
+        self.stats.object.sync()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,316 @@
+//! Dialogue boxes stages can show between their events.
+use std::{collections::VecDeque, sync::Arc};
+
+use anyhow::Result;
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{accessibility, input::Action, load_material, locale};
+
+use super::{
+    tween::{Easing, Tween},
+    Camera,
+};
+
+/// Characters of text revealed every second.
+const CHARS_PER_SECOND: f32 = 40.0;
+const BOX_POSITION: Vec2 = vec2(0.0, 0.7);
+const BOX_SIZE: Vec2 = vec2(1.3, 0.25);
+const PORTRAIT_SIZE: Vec2 = vec2(0.18, 0.18);
+
+/// One box of text said by a speaker.
+///
+/// The speaker, text and choices get translated if they are localization keys.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DialogueLine {
+    pub speaker: String,
+    /// Path of the portrait texture in the assets.
+    pub portrait: Option<String>,
+    pub text: String,
+    /// Answers the player has to pick from with the number keys.
+    pub choices: Vec<Choice>,
+    /// Played once the line shows up.
+    pub effect: Option<ScreenEffect>,
+}
+
+impl DialogueLine {
+    pub fn new(speaker: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            speaker: speaker.into(),
+            portrait: None,
+            text: text.into(),
+            choices: vec![],
+            effect: None,
+        }
+    }
+
+    pub fn portrait(mut self, portrait: impl Into<String>) -> Self {
+        self.portrait = Some(portrait.into());
+        self
+    }
+
+    pub fn choice(mut self, text: impl Into<String>, flag: impl Into<String>) -> Self {
+        self.choices.push(Choice {
+            text: text.into(),
+            flag: flag.into(),
+        });
+        self
+    }
+
+    pub fn effect(mut self, effect: ScreenEffect) -> Self {
+        self.effect = Some(effect);
+        self
+    }
+}
+
+/// An answer that sets a flag in the game state when picked.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Choice {
+    pub text: String,
+    pub flag: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ScreenEffect {
+    Shake,
+    /// Flashes the screen white.
+    Flash,
+    /// Darkens everything behind the dialogue box while the line is shown.
+    Darken,
+}
+
+/// The dialogue box and the conversation currently shown in it.
+pub struct Dialogue {
+    lines: VecDeque<DialogueLine>,
+    /// Translated text of the current line.
+    line_text: String,
+    /// Characters of the current line revealed so far.
+    revealed: f32,
+    /// Whether the current line still has to play its effect.
+    new_line: bool,
+    pause_fire: bool,
+
+    background: Object,
+    portrait: Object,
+    speaker: Label<Object>,
+    text: Label<Object>,
+    choices: Label<Object>,
+    /// Covers the screen for the flash and darken effects.
+    overlay: Object,
+    overlay_alpha: Tween<f32>,
+}
+
+impl Dialogue {
+    pub fn new(layer: &Arc<Layer>) -> Result<Self> {
+        let overlay = NewObjectBuilder::default()
+            .appearance(
+                Appearance::new()
+                    .model(Some(Model::Square))
+                    .transform(Transform::default().size(vec2(4.0, 1.0)))
+                    .color(Color::from_rgba(0.0, 0.0, 0.0, 0.0)),
+            )
+            .build()?
+            .init(layer)?;
+
+        let background = NewObjectBuilder::default()
+            .appearance(
+                Appearance::new()
+                    .model(Some(Model::Square))
+                    .transform(Transform::default().size(BOX_SIZE))
+                    .color(Color::from_rgba(0.0, 0.0, 0.0, 0.8))
+                    .visible(false),
+            )
+            .transform(Transform::default().position(BOX_POSITION))
+            .build()?
+            .init(layer)?;
+
+        let portrait = NewObjectBuilder::default()
+            .appearance(
+                Appearance::new()
+                    .model(Some(Model::Square))
+                    .transform(Transform::default().size(PORTRAIT_SIZE))
+                    .visible(false),
+            )
+            .transform(
+                Transform::default()
+                    .position(BOX_POSITION - vec2(BOX_SIZE.x - PORTRAIT_SIZE.x - 0.04, 0.0)),
+            )
+            .build()?
+            .init(layer)?;
+
+        let label = |align: Direction, scale: f32, color: Color| {
+            Label::new(
+                locale::font(),
+                LabelCreateInfo::default()
+                    .align(align)
+                    .scale(accessibility::text_size(Vec2::splat(scale)))
+                    .appearance(
+                        Appearance::default()
+                            .transform(
+                                Transform::default()
+                                    .position(BOX_POSITION + vec2(0.2, 0.0))
+                                    .size(BOX_SIZE - vec2(0.25, 0.03)),
+                            )
+                            .color(color),
+                    ),
+            )
+            .init(layer)
+        };
+
+        Ok(Self {
+            lines: VecDeque::new(),
+            line_text: String::new(),
+            revealed: 0.0,
+            new_line: false,
+            pause_fire: false,
+            background,
+            portrait,
+            speaker: label(Direction::Nw, 45.0, Color::from_rgb(0.9, 0.3, 0.1))?,
+            text: label(Direction::W, 40.0, Color::WHITE)?,
+            choices: label(Direction::Sw, 40.0, Color::from_rgb(0.8, 0.8, 0.5))?,
+            overlay,
+            overlay_alpha: Tween::resting(0.0),
+        })
+    }
+
+    /// Shows the lines one after another.
+    pub fn start(&mut self, lines: Vec<DialogueLine>, pause_fire: bool) -> Result<()> {
+        self.lines = lines.into();
+        self.pause_fire = pause_fire;
+        self.show_line()
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Whether enemies should hold their fire right now.
+    pub fn pauses_fire(&self) -> bool {
+        self.is_open() && self.pause_fire
+    }
+
+    /// Handles an action key press, returning the flag of the choice picked with it.
+    pub fn input(&mut self, action: Action) -> Result<Option<String>> {
+        let Some(line) = self.lines.front() else {
+            return Ok(None);
+        };
+        let length = self.line_text.chars().count() as f32;
+        if self.revealed < length {
+            // The first press skips the typing.
+            if matches!(action, Action::Advance | Action::Choose(_)) {
+                self.revealed = length;
+            }
+            return Ok(None);
+        }
+        let flag = match action {
+            Action::Advance if line.choices.is_empty() => None,
+            Action::Choose(number) => {
+                let Some(choice) = line.choices.get(number as usize - 1) else {
+                    return Ok(None);
+                };
+                Some(choice.flag.clone())
+            }
+            _ => return Ok(None),
+        };
+        self.lines.pop_front();
+        self.show_line()?;
+        Ok(flag)
+    }
+
+    /// Sets up the boxes for the line at the front.
+    fn show_line(&mut self) -> Result<()> {
+        self.revealed = 0.0;
+        self.new_line = true;
+        let Some(line) = self.lines.front() else {
+            self.background.appearance.set_visible(false);
+            self.portrait.appearance.set_visible(false);
+            self.background.sync()?;
+            self.portrait.sync()?;
+            for label in [&mut self.speaker, &mut self.text, &mut self.choices] {
+                label.update_text("");
+            }
+            self.overlay_alpha.retarget(0.0);
+            return Ok(());
+        };
+
+        self.background.appearance.set_visible(true);
+        self.background.sync()?;
+        match &line.portrait {
+            Some(portrait) => {
+                let material = load_material(&asset(portrait)?, 1);
+                self.portrait.appearance = self
+                    .portrait
+                    .appearance
+                    .clone()
+                    .material(material)
+                    .visible(true);
+            }
+            None => self.portrait.appearance.set_visible(false),
+        }
+        self.portrait.sync()?;
+
+        self.line_text = locale::text(&line.text);
+        self.speaker.update_text(locale::text(&line.speaker));
+        self.text.update_text("");
+        let choices = line
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(index, choice)| format!("[{}] {}", index + 1, locale::text(&choice.text)))
+            .collect::<Vec<_>>()
+            .join("   ");
+        self.choices.update_text(choices);
+        Ok(())
+    }
+
+    pub fn update(&mut self, camera: &mut Camera) -> Result<()> {
+        if let Some(line) = self.lines.front() {
+            if self.new_line {
+                self.new_line = false;
+                match line.effect {
+                    Some(ScreenEffect::Shake) => camera.shake(),
+                    Some(ScreenEffect::Flash) => {
+                        let strength = if accessibility::get().reduce_flashes {
+                            0.2
+                        } else {
+                            0.8
+                        };
+                        self.overlay.appearance.set_color(Color::WHITE);
+                        self.overlay_alpha = Tween::new(strength, 0.0, 0.5).easing(Easing::QuadOut);
+                    }
+                    Some(ScreenEffect::Darken) => {
+                        self.overlay.appearance.set_color(Color::BLACK);
+                        self.overlay_alpha = Tween::new(0.0, 0.6, 0.4);
+                    }
+                    None => self.overlay_alpha.retarget(0.0),
+                }
+            }
+
+            let length = self.line_text.chars().count();
+            let shown = self.revealed as usize;
+            self.revealed += TIME.delta_time() as f32 * CHARS_PER_SECOND;
+            let revealed = (self.revealed as usize).min(length);
+            if revealed != shown || self.text.text.is_empty() {
+                self.text
+                    .update_text(self.line_text.chars().take(revealed).collect::<String>());
+            }
+        }
+
+        let alpha = self.overlay_alpha.update();
+        self.overlay.appearance.get_color_mut().set_a(alpha);
+        self.overlay.sync()?;
+        self.speaker.sync();
+        self.text.sync();
+        self.choices.sync();
+        Ok(())
+    }
+
+    pub fn remove(self) {
+        let _ = self.overlay.remove();
+        let _ = self.background.remove();
+        let _ = self.portrait.remove();
+        let _ = self.speaker.object.remove();
+        let _ = self.text.object.remove();
+        let _ = self.choices.object.remove();
+    }
+}
@@ -1,17 +1,59 @@
-use anyhow::Result;
 use let_engine::prelude::*;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
 
-use crate::game::sounds::Sounds;
-
-use super::projectiles::ProjectileType;
+use self::status::StatusEffect;
+use super::{
+    debug::DebugInfo, particles::ParticleSystem, powerups::PowerUpKind, projectiles::ProjectileType,
+};
 
+pub mod movement;
+pub mod spawn;
+pub mod status;
 pub mod target;
 
 pub trait Enemy: Send + Sync {
-    fn damage_if_id_right(&mut self, ids: &[usize], damage: f32) -> (f32, bool);
+    /// The ID of the collider projectiles hit.
+    fn id(&self) -> usize;
+    /// Reacts to getting hit by a projectile.
+    fn hit(&mut self, hit: &Hit) -> HitOutcome;
     fn update(&mut self) -> EnemyMessage;
+    fn position(&self) -> Vec2;
+    /// Pauses or resumes the sounds of the enemy.
+    fn set_sounds_paused(&self, paused: bool);
+    /// Removes the enemy without any effects.
     fn remove(&mut self);
+    /// Removes the enemy after it got killed.
+    fn destroy(&mut self, particles: &mut ParticleSystem);
+    fn debug_info(&self) -> DebugInfo;
+}
+
+/// A returned projectile hitting an enemy.
+#[derive(Clone, Copy, Debug)]
+pub struct Hit<'a> {
+    pub kind: ProjectileType,
+    pub position: Vec2,
+    pub damage: f32,
+    /// Whether the projectile was returned with a hard shot.
+    pub critical: bool,
+    pub effects: &'a [StatusEffect],
+}
+
+/// What happened to an enemy after getting hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HitOutcome {
+    /// The enemy took damage and is still alive.
+    Hit,
+    /// The enemy died and should be destroyed.
+    Killed(Kill),
+    /// The enemy did not take any damage.
+    Blocked,
+}
+
+/// The reward for killing an enemy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Kill {
+    pub score: u32,
+    pub drop: Option<PowerUpKind>,
 }
 
 pub enum EnemyMessage {
@@ -22,8 +64,13 @@ pub enum EnemyMessage {
         direction: Vec2,
     },
     Particle, //(Vec<Box<dyn Particles>>),
+    /// Killed by damage over time.
+    Died(Kill),
+    /// Left the screen at the end of its movement.
+    Escaped,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum EnemyType {
     Target,
     Fairy,
@@ -53,10 +100,6 @@ pub enum EnemyType {
 }
 
 impl EnemyType {
-    pub fn spawn(&self, layer: &Arc<Layer>, sounds: &Sounds) -> Result<Box<dyn Enemy>> {
-        Ok(match self {
-            Self::Target => Box::new(target::Target::new(layer, sounds.clone())?),
-            _ => todo!(),
-        })
-    }
+    /// All enemies that are implemented and can be spawned.
+    pub const SPAWNABLE: [EnemyType; 1] = [EnemyType::Target];
 }
@@ -0,0 +1,428 @@
+//! Reusable enemy movement, evaluated purely from the seconds an enemy has been alive.
+use std::f32::consts::TAU;
+
+use let_engine::prelude::*;
+
+use crate::objects::tween::Easing;
+
+/// A curve that gives a position for every point in time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Path {
+    /// Stays at the point.
+    Fixed(Vec2),
+    /// Moves in a straight line.
+    Line {
+        from: Vec2,
+        to: Vec2,
+        duration: f32,
+        easing: Easing,
+    },
+    /// Cubic bezier curve from the first to the last point.
+    Bezier {
+        points: [Vec2; 4],
+        duration: f32,
+        easing: Easing,
+    },
+    /// Catmull-Rom spline passing through all points.
+    Spline {
+        points: Vec<Vec2>,
+        duration: f32,
+        looping: bool,
+    },
+    /// Bobs up and down around the center.
+    Bob {
+        center: Vec2,
+        amplitude: f32,
+        speed: f32,
+    },
+    /// Circles around the anchor once every period.
+    Orbit {
+        anchor: Vec2,
+        radius: f32,
+        period: f32,
+        phase: f32,
+    },
+    /// Both paths added together, for example to orbit a moving anchor.
+    Sum(Box<Path>, Box<Path>),
+}
+
+type PathBuilder = fn() -> Path;
+
+/// The paths stages and enemies can refer to by name.
+///
+/// All of them are offsets around zero, so entries end and idles and exits start at zero.
+const NAMED_PATHS: [(&str, PathBuilder); 11] = [
+    ("none", || Path::Fixed(Vec2::ZERO)),
+    ("enter_right", || Path::Line {
+        from: vec2(6.0, 0.0),
+        to: Vec2::ZERO,
+        duration: 5.0,
+        easing: Easing::ExpoOut,
+    }),
+    ("enter_top", || Path::Bezier {
+        points: [vec2(1.5, -2.0), vec2(1.0, -0.5), vec2(0.6, 0.0), Vec2::ZERO],
+        duration: 2.5,
+        easing: Easing::CubicOut,
+    }),
+    ("enter_bottom", || Path::Bezier {
+        points: [vec2(1.5, 2.0), vec2(1.0, 0.5), vec2(0.6, 0.0), Vec2::ZERO],
+        duration: 2.5,
+        easing: Easing::CubicOut,
+    }),
+    ("enter_swoop", || Path::Spline {
+        points: vec![
+            vec2(4.0, -1.5),
+            vec2(1.0, -0.8),
+            vec2(-0.5, 0.0),
+            vec2(0.5, 0.6),
+            Vec2::ZERO,
+        ],
+        duration: 4.0,
+        looping: false,
+    }),
+    ("exit_right", || Path::Line {
+        from: Vec2::ZERO,
+        to: vec2(6.0, 0.0),
+        duration: 3.0,
+        easing: Easing::CubicIn,
+    }),
+    ("exit_top", || Path::Line {
+        from: Vec2::ZERO,
+        to: vec2(0.5, -3.0),
+        duration: 2.0,
+        easing: Easing::QuadIn,
+    }),
+    ("bob", || Path::Bob {
+        center: Vec2::ZERO,
+        amplitude: 0.05,
+        speed: 1.0,
+    }),
+    ("sweep", || Path::Spline {
+        points: vec![
+            Vec2::ZERO,
+            vec2(-0.1, 0.6),
+            vec2(-0.2, 0.0),
+            vec2(-0.1, -0.6),
+        ],
+        duration: 8.0,
+        looping: true,
+    }),
+    ("figure_eight", || Path::Spline {
+        points: vec![
+            Vec2::ZERO,
+            vec2(0.2, -0.2),
+            vec2(0.4, 0.0),
+            vec2(0.2, 0.2),
+            Vec2::ZERO,
+            vec2(-0.2, -0.2),
+            vec2(-0.4, 0.0),
+            vec2(-0.2, 0.2),
+        ],
+        duration: 6.0,
+        looping: true,
+    }),
+    ("orbit", || Path::Orbit {
+        anchor: vec2(-0.2, 0.0),
+        radius: 0.2,
+        period: 5.0,
+        phase: 0.0,
+    }),
+];
+
+impl Path {
+    /// Names of all paths stages and enemies can refer to.
+    pub const NAMES: [&'static str; NAMED_PATHS.len()] = {
+        let mut names = [""; NAMED_PATHS.len()];
+        let mut index = 0;
+        while index < names.len() {
+            names[index] = NAMED_PATHS[index].0;
+            index += 1;
+        }
+        names
+    };
+
+    pub fn named(name: &str) -> Option<Self> {
+        NAMED_PATHS
+            .iter()
+            .find(|(named, _)| *named == name)
+            .map(|(_, path)| path())
+    }
+
+    /// The position at the given seconds since the start of the path.
+    pub fn position(&self, time: f32) -> Vec2 {
+        match self {
+            Path::Fixed(position) => *position,
+            Path::Line {
+                from,
+                to,
+                duration,
+                easing,
+            } => from.lerp(*to, easing.apply(progress(time, *duration))),
+            Path::Bezier {
+                points,
+                duration,
+                easing,
+            } => bezier(points, easing.apply(progress(time, *duration))),
+            Path::Spline {
+                points,
+                duration,
+                looping,
+            } => spline(points, time / duration.max(f32::EPSILON), *looping),
+            Path::Bob {
+                center,
+                amplitude,
+                speed,
+            } => *center + vec2(0.0, (time * speed).sin() * amplitude),
+            Path::Orbit {
+                anchor,
+                radius,
+                period,
+                phase,
+            } => *anchor + Vec2::from_angle(phase + TAU * time / period) * *radius,
+            Path::Sum(a, b) => a.position(time) + b.position(time),
+        }
+    }
+
+    /// Seconds until the path ends or `None` if it goes on forever.
+    pub fn duration(&self) -> Option<f32> {
+        match self {
+            Path::Line { duration, .. } | Path::Bezier { duration, .. } => Some(*duration),
+            Path::Spline {
+                duration, looping, ..
+            } => (!looping).then_some(*duration),
+            Path::Fixed(_) | Path::Bob { .. } | Path::Orbit { .. } => None,
+            Path::Sum(a, b) => match (a.duration(), b.duration()) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The same path moved by `offset`.
+    pub fn offset(self, offset: Vec2) -> Self {
+        Path::Sum(Box::new(self), Box::new(Path::Fixed(offset)))
+    }
+}
+
+/// Linear progress from 0 to 1 through a path of the given duration.
+fn progress(time: f32, duration: f32) -> f32 {
+    if duration <= 0.0 {
+        1.0
+    } else {
+        (time / duration).clamp(0.0, 1.0)
+    }
+}
+
+fn bezier(points: &[Vec2; 4], t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    points[0] * (u * u * u)
+        + points[1] * (3.0 * u * u * t)
+        + points[2] * (3.0 * u * t * t)
+        + points[3] * (t * t * t)
+}
+
+/// Samples a Catmull-Rom spline where `t` goes from 0 to 1 through all points.
+fn spline(points: &[Vec2], t: f32, looping: bool) -> Vec2 {
+    let len = points.len();
+    match len {
+        0 => return Vec2::ZERO,
+        1 => return points[0],
+        _ => (),
+    }
+    let segments = if looping { len } else { len - 1 };
+    let t = if looping {
+        t.rem_euclid(1.0)
+    } else {
+        t.clamp(0.0, 1.0)
+    } * segments as f32;
+    let segment = (t as usize).min(segments - 1);
+    let t = t - segment as f32;
+
+    let point = |index: isize| {
+        if looping {
+            points[index.rem_euclid(len as isize) as usize]
+        } else {
+            points[index.clamp(0, len as isize - 1) as usize]
+        }
+    };
+    let segment = segment as isize;
+    let (p0, p1, p2, p3) = (
+        point(segment - 1),
+        point(segment),
+        point(segment + 1),
+        point(segment + 2),
+    );
+
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * (t * t)
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * (t * t * t))
+        * 0.5
+}
+
+/// Where an enemy is over its lifetime: flying in, moving around its anchor and flying away.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Movement {
+    pub anchor: Vec2,
+    /// Offset from the anchor while flying in.
+    pub entry: Path,
+    /// Offset from the anchor once the entry is done.
+    pub idle: Path,
+    /// Seconds of idling before leaving along the path starting where the idle path ended.
+    pub exit: Option<(f32, Path)>,
+}
+
+impl Movement {
+    pub fn new(anchor: Vec2) -> Self {
+        Self {
+            anchor,
+            entry: Path::Fixed(Vec2::ZERO),
+            idle: Path::Fixed(Vec2::ZERO),
+            exit: None,
+        }
+    }
+
+    /// Looks up the entry and idle paths by name.
+    pub fn named(anchor: Vec2, entry: &str, idle: &str) -> Option<Self> {
+        Some(
+            Self::new(anchor)
+                .entry(Path::named(entry)?)
+                .idle(Path::named(idle)?),
+        )
+    }
+
+    pub fn entry(mut self, entry: Path) -> Self {
+        self.entry = entry;
+        self
+    }
+
+    pub fn idle(mut self, idle: Path) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    pub fn exit(mut self, after: f32, exit: Path) -> Self {
+        self.exit = Some((after, exit));
+        self
+    }
+
+    pub fn position(&self, time: f32) -> Vec2 {
+        let entry = self.entry.duration().unwrap_or(0.0);
+        if time < entry {
+            return self.anchor + self.entry.position(time);
+        }
+        let time = time - entry;
+        match &self.exit {
+            Some((after, exit)) if time >= *after => {
+                self.anchor + self.idle.position(*after) + exit.position(time - after)
+            }
+            _ => self.anchor + self.idle.position(time),
+        }
+    }
+
+    /// Whether the enemy is done leaving the screen at the given time.
+    pub fn finished(&self, time: f32) -> bool {
+        let Some((after, exit)) = &self.exit else {
+            return false;
+        };
+        let entry = self.entry.duration().unwrap_or(0.0);
+        exit.duration()
+            .is_some_and(|duration| time >= entry + after + duration)
+    }
+}
+
+/// The positions of enemies moving together as a group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Formation {
+    /// Offsets of every member from the anchor of the group.
+    pub slots: Vec<Vec2>,
+}
+
+impl Formation {
+    /// Names of all formations stages can refer to.
+    ///
+    /// Single enemies are spawned without a formation.
+    pub const NAMES: [&'static str; 4] = ["column", "row", "v", "ring"];
+
+    /// The formations stages can refer to by name with the given amount of members.
+    pub fn named(name: &str, count: usize) -> Option<Self> {
+        const SPACING: f32 = 0.3;
+        if !Self::NAMES.contains(&name) {
+            return None;
+        }
+        let center = (count.max(1) - 1) as f32 * 0.5;
+        let slots = (0..count).map(|index| {
+            let index = index as f32;
+            match name {
+                "column" => Some(vec2(0.0, (index - center) * SPACING)),
+                "row" => Some(vec2((index - center) * SPACING, 0.0)),
+                "v" => {
+                    let offset = index - center;
+                    Some(vec2(offset.abs() * SPACING, offset * SPACING))
+                }
+                "ring" => Some(Vec2::from_angle(TAU * index / count as f32) * SPACING),
+                _ => None,
+            }
+        });
+        Some(Self {
+            slots: slots.collect::<Option<_>>()?,
+        })
+    }
+
+    /// The anchor of every member of a group anchored at `anchor`.
+    ///
+    /// Members following the same paths from the same time move as one.
+    pub fn anchors(&self, anchor: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        self.slots.iter().map(move |slot| anchor + *slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRIES: [&str; 4] = ["enter_right", "enter_top", "enter_bottom", "enter_swoop"];
+    const IDLES: [&str; 5] = ["none", "bob", "sweep", "figure_eight", "orbit"];
+
+    #[test]
+    fn continuous_at_entry_end() {
+        let anchor = vec2(2.0, 0.3);
+        for entry in ENTRIES {
+            for idle in IDLES {
+                let movement = Movement::named(anchor, entry, idle).unwrap();
+                let end = movement.entry.duration().unwrap();
+                let before = movement.position(end - 0.001);
+                let after = movement.position(end + 0.001);
+                assert!(
+                    before.distance(after) < 0.01,
+                    "{entry} into {idle} jumps from {before} to {after}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn entries_end_and_idles_start_at_zero() {
+        for entry in ENTRIES {
+            let path = Path::named(entry).unwrap();
+            let end = path.position(path.duration().unwrap());
+            assert!(end.length() < 1e-4, "{entry} ends at {end}");
+        }
+        for idle in IDLES {
+            let start = Path::named(idle).unwrap().position(0.0);
+            assert!(start.length() < 1e-4, "{idle} starts at {start}");
+        }
+    }
+
+    #[test]
+    fn formations_have_one_anchor_per_member() {
+        for name in Formation::NAMES {
+            for count in 0..8 {
+                let formation = Formation::named(name, count).unwrap();
+                assert_eq!(formation.anchors(Vec2::ZERO).count(), count, "{name}");
+            }
+        }
+        assert!(Formation::named("square", 3).is_none());
+        assert!(Formation::named("single", 3).is_none());
+    }
+}
@@ -0,0 +1,186 @@
+//! Parameters stages can spawn enemies with.
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use let_engine::prelude::*;
+use rand::random;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{difficulty::Difficulty, sounds::Sounds},
+    objects::powerups::{PowerUpKind, DROP_CHANCE},
+};
+
+use super::{
+    movement::{Movement, Path},
+    target, Enemy, EnemyType,
+};
+
+/// An enemy to spawn with optional overrides of its defaults.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Spawn {
+    pub enemy: EnemyType,
+    /// The anchor the enemy moves around.
+    pub position: Option<Vec2>,
+    /// Name of the path the enemy flies in with.
+    pub entry: Option<String>,
+    /// Name of the path the enemy moves along after flying in.
+    pub idle: Option<String>,
+    pub hp: Option<f32>,
+    pub pattern: Option<FirePattern>,
+    pub drops: Option<DropTable>,
+}
+
+impl Spawn {
+    pub fn new(enemy: EnemyType) -> Self {
+        Self {
+            enemy,
+            position: None,
+            entry: None,
+            idle: None,
+            hp: None,
+            pattern: None,
+            drops: None,
+        }
+    }
+
+    pub fn position(mut self, position: Vec2) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn entry(mut self, entry: impl Into<String>) -> Self {
+        self.entry = Some(entry.into());
+        self
+    }
+
+    pub fn idle(mut self, idle: impl Into<String>) -> Self {
+        self.idle = Some(idle.into());
+        self
+    }
+
+    pub fn hp(mut self, hp: f32) -> Self {
+        self.hp = Some(hp);
+        self
+    }
+
+    pub fn pattern(mut self, pattern: FirePattern) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    pub fn drops(mut self, drops: DropTable) -> Self {
+        self.drops = Some(drops);
+        self
+    }
+
+    /// Checks that the enemy can be spawned and the paths it overrides exist.
+    pub fn validate(&self) -> Result<()> {
+        if !EnemyType::SPAWNABLE.contains(&self.enemy) {
+            return Err(anyhow!("{:?} can not be spawned yet", self.enemy));
+        }
+        match self
+            .entry
+            .iter()
+            .chain(&self.idle)
+            .find(|name| Path::named(name).is_none())
+        {
+            Some(name) => Err(anyhow!("Unknown path {name}")),
+            None => Ok(()),
+        }
+    }
+
+    /// The movement of the enemy, using the given defaults for everything not overridden.
+    pub fn movement(&self, anchor: Vec2, entry: &str, idle: &str) -> Result<Movement> {
+        let anchor = self.position.unwrap_or(anchor);
+        let entry = self.entry.as_deref().unwrap_or(entry);
+        let idle = self.idle.as_deref().unwrap_or(idle);
+        Movement::named(anchor, entry, idle)
+            .ok_or_else(|| anyhow!("Unknown path {entry} or {idle}"))
+    }
+
+    pub fn spawn(
+        &self,
+        layer: &Arc<Layer>,
+        sounds: &Sounds,
+        difficulty: Difficulty,
+    ) -> Result<Box<dyn Enemy>> {
+        Ok(match self.enemy {
+            EnemyType::Target => Box::new(target::Target::new(
+                layer,
+                sounds.clone(),
+                difficulty,
+                self,
+            )?),
+            enemy => return Err(anyhow!("{enemy:?} can not be spawned yet")),
+        })
+    }
+}
+
+impl From<EnemyType> for Spawn {
+    fn from(enemy: EnemyType) -> Self {
+        Self::new(enemy)
+    }
+}
+
+/// How an enemy shoots its projectiles.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum FirePattern {
+    /// From somewhere to the right at a random height of the paddle side.
+    Random,
+    /// Straight to the left from the enemy.
+    Straight,
+    /// Several shots in quick succession fanning out over `spread` radians.
+    Burst { count: u32, spread: f32 },
+}
+
+/// What a killed enemy can drop.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DropTable {
+    /// Chance of dropping anything at all.
+    pub chance: f32,
+    /// Power-ups with their weights.
+    pub kinds: Vec<(PowerUpKind, u32)>,
+}
+
+impl DropTable {
+    /// Drops nothing.
+    pub fn none() -> Self {
+        Self {
+            chance: 0.0,
+            kinds: vec![],
+        }
+    }
+
+    /// Always drops the given power-up.
+    pub fn always(kind: PowerUpKind) -> Self {
+        Self {
+            chance: 1.0,
+            kinds: vec![(kind, 1)],
+        }
+    }
+
+    pub fn roll(&self) -> Option<PowerUpKind> {
+        let total: u32 = self.kinds.iter().map(|(_, weight)| weight).sum();
+        if total == 0 || random::<f32>() >= self.chance {
+            return None;
+        }
+        let mut pick = random::<u32>() % total;
+        for (kind, weight) in &self.kinds {
+            if pick < *weight {
+                return Some(*kind);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+impl Default for DropTable {
+    fn default() -> Self {
+        Self {
+            chance: DROP_CHANCE,
+            kinds: PowerUpKind::ALL.iter().map(|kind| (*kind, 1)).collect(),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Status effects projectiles leave on the enemies they hit.
+use let_engine::prelude::*;
+
+/// An effect applied to an enemy on hit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusEffect {
+    /// Stops the enemy from moving and shooting for the given seconds.
+    Stun(f32),
+    /// Pushes the enemy with the given velocity that slows down over time.
+    Knockback(Vec2),
+    /// Damages the enemy every second for some time.
+    Burn { damage: f32, duration: f32 },
+    /// Like burning, but stacks with itself.
+    Bleed { damage: f32, duration: f32 },
+    /// Multiplies the speed of the enemy by `factor` for some time.
+    Slow { factor: f32, duration: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+    Stun,
+    Knockback,
+    Burn,
+    Bleed,
+    Slow,
+}
+
+impl StatusEffect {
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            StatusEffect::Stun(_) => StatusKind::Stun,
+            StatusEffect::Knockback(_) => StatusKind::Knockback,
+            StatusEffect::Burn { .. } => StatusKind::Burn,
+            StatusEffect::Bleed { .. } => StatusKind::Bleed,
+            StatusEffect::Slow { .. } => StatusKind::Slow,
+        }
+    }
+
+    /// Scales the strength of the effect by the multiplier of a resistance or weakness.
+    fn scaled(self, multiplier: f32) -> Self {
+        match self {
+            StatusEffect::Stun(duration) => StatusEffect::Stun(duration * multiplier),
+            StatusEffect::Knockback(velocity) => StatusEffect::Knockback(velocity * multiplier),
+            StatusEffect::Burn { damage, duration } => StatusEffect::Burn {
+                damage: damage * multiplier,
+                duration,
+            },
+            StatusEffect::Bleed { damage, duration } => StatusEffect::Bleed {
+                damage: damage * multiplier,
+                duration,
+            },
+            StatusEffect::Slow { factor, duration } => StatusEffect::Slow {
+                factor,
+                duration: duration * multiplier,
+            },
+        }
+    }
+
+    /// Seconds the effect stays active.
+    fn duration(&self) -> f32 {
+        match self {
+            StatusEffect::Stun(duration) => *duration,
+            StatusEffect::Knockback(_) => 0.0,
+            StatusEffect::Burn { duration, .. }
+            | StatusEffect::Bleed { duration, .. }
+            | StatusEffect::Slow { duration, .. } => *duration,
+        }
+    }
+}
+
+/// How strong status effects are on an enemy.
+///
+/// A multiplier of 0 makes it immune, values above 1 are weaknesses.
+#[derive(Clone, Debug, Default)]
+pub struct Resistances(Vec<(StatusKind, f32)>);
+
+impl Resistances {
+    pub fn with(mut self, kind: StatusKind, multiplier: f32) -> Self {
+        self.0.push((kind, multiplier));
+        self
+    }
+
+    pub fn multiplier(&self, kind: StatusKind) -> f32 {
+        self.0
+            .iter()
+            .find(|(resisted, _)| *resisted == kind)
+            .map(|(_, multiplier)| *multiplier)
+            .unwrap_or(1.0)
+    }
+}
+
+/// What the active status effects do to an enemy this frame.
+#[derive(Clone, Copy, Debug)]
+pub struct StatusFrame {
+    pub damage: f32,
+    pub stunned: bool,
+    /// Multiplier of the speed the enemy moves and shoots with.
+    pub speed: f32,
+    /// Distance the enemy got pushed this frame.
+    pub push: Vec2,
+}
+
+/// The status effects currently active on an enemy.
+#[derive(Clone, Debug, Default)]
+pub struct Statuses {
+    resistances: Resistances,
+    /// Active effects with their remaining seconds.
+    active: Vec<(StatusEffect, f32)>,
+    knockback: Vec2,
+}
+
+impl Statuses {
+    pub fn new(resistances: Resistances) -> Self {
+        Self {
+            resistances,
+            ..Default::default()
+        }
+    }
+
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let multiplier = self.resistances.multiplier(effect.kind());
+        if multiplier <= 0.0 {
+            return;
+        }
+        let effect = effect.scaled(multiplier);
+        match effect {
+            StatusEffect::Knockback(velocity) => self.knockback += velocity,
+            StatusEffect::Bleed { .. } => self.active.push((effect, effect.duration())),
+            // Everything else refreshes instead of stacking.
+            _ => {
+                self.active
+                    .retain(|(active, _)| active.kind() != effect.kind());
+                self.active.push((effect, effect.duration()));
+            }
+        }
+    }
+
+    pub fn is_active(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|(effect, _)| effect.kind() == kind)
+    }
+
+    /// Counts down the effects by the delta time of the game and sums up what they do this frame.
+    pub fn update(&mut self) -> StatusFrame {
+        self.advance(TIME.delta_time() as f32)
+    }
+
+    /// Counts down the effects by `delta_time` seconds and sums up what they do in that time.
+    pub fn advance(&mut self, delta_time: f32) -> StatusFrame {
+        let mut frame = StatusFrame {
+            damage: 0.0,
+            stunned: false,
+            speed: 1.0,
+            push: self.knockback * delta_time,
+        };
+        self.knockback *= (1.0 - delta_time * 6.0).max(0.0);
+
+        for (effect, remaining) in &mut self.active {
+            let time = delta_time.min(*remaining);
+            match effect {
+                StatusEffect::Stun(_) => frame.stunned = true,
+                StatusEffect::Burn { damage, .. } | StatusEffect::Bleed { damage, .. } => {
+                    frame.damage += *damage * time
+                }
+                StatusEffect::Slow { factor, .. } => frame.speed *= *factor,
+                StatusEffect::Knockback(_) => (),
+            }
+            *remaining -= delta_time;
+        }
+        self.active.retain(|(_, remaining)| *remaining > 0.0);
+        if frame.stunned {
+            frame.speed = 0.0;
+        }
+        frame
+    }
+
+    /// The color showing the most important active effect.
+    pub fn tint(&self) -> Option<Color> {
+        [
+            (StatusKind::Stun, Color::from_rgb(1.5, 1.5, 0.4)),
+            (StatusKind::Burn, Color::from_rgb(1.6, 0.7, 0.2)),
+            (StatusKind::Bleed, Color::from_rgb(1.2, 0.2, 0.2)),
+            (StatusKind::Slow, Color::from_rgb(0.5, 0.7, 1.4)),
+        ]
+        .into_iter()
+        .find(|(kind, _)| self.is_active(*kind))
+        .map(|(_, color)| color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLEED: StatusEffect = StatusEffect::Bleed {
+        damage: 1.0,
+        duration: 2.0,
+    };
+    const BURN: StatusEffect = StatusEffect::Burn {
+        damage: 1.0,
+        duration: 2.0,
+    };
+
+    #[test]
+    fn bleed_stacks() {
+        let mut statuses = Statuses::default();
+        statuses.apply(BLEED);
+        statuses.apply(BLEED);
+        assert_eq!(statuses.advance(0.5).damage, 1.0);
+    }
+
+    #[test]
+    fn other_effects_refresh() {
+        let mut statuses = Statuses::default();
+        statuses.apply(BURN);
+        statuses.advance(1.5);
+        statuses.apply(BURN);
+        assert_eq!(statuses.advance(0.5).damage, 0.5);
+        assert!(statuses.is_active(StatusKind::Burn));
+        assert_eq!(statuses.advance(1.0).damage, 1.0);
+    }
+
+    #[test]
+    fn zero_multiplier_is_immune() {
+        let mut statuses = Statuses::new(
+            Resistances::default()
+                .with(StatusKind::Stun, 0.0)
+                .with(StatusKind::Burn, 2.0),
+        );
+        statuses.apply(StatusEffect::Stun(1.0));
+        statuses.apply(BURN);
+        assert!(!statuses.is_active(StatusKind::Stun));
+        let frame = statuses.advance(0.5);
+        assert!(!frame.stunned);
+        assert_eq!(frame.damage, 1.0);
+    }
+
+    #[test]
+    fn effects_expire() {
+        let mut statuses = Statuses::default();
+        statuses.apply(StatusEffect::Stun(1.0));
+        statuses.apply(BURN);
+        assert_eq!(statuses.advance(0.5).speed, 0.0);
+        statuses.advance(0.5);
+        assert!(!statuses.is_active(StatusKind::Stun));
+        // Only the time the burn had left deals damage.
+        assert_eq!(statuses.advance(5.0).damage, 1.0);
+        assert!(!statuses.is_active(StatusKind::Burn));
+        assert_eq!(statuses.advance(0.5).speed, 1.0);
+    }
+}
@@ -1,42 +1,70 @@
 use once_cell::sync::Lazy;
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{f32::consts::PI, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use let_engine::prelude::*;
 use rand::random;
 
 use crate::{
-    game::{load_material, sounds::Sounds, SAMPLER},
-    objects::{particles::debris_particle, projectiles::ProjectileType},
+    game::{accessibility, difficulty::Difficulty, load_material, sounds::Sounds, SAMPLER},
+    objects::{
+        clock::GameInstant,
+        debug::{ColliderShape, DebugInfo},
+        particles::{Emitter, ParticleSystem},
+        projectiles::ProjectileType,
+    },
     HEIGHT,
 };
 
-use super::{Enemy, EnemyMessage};
+use super::{
+    movement::Movement,
+    spawn::{DropTable, FirePattern, Spawn},
+    status::{Resistances, StatusKind, Statuses},
+    Enemy, EnemyMessage, Hit, HitOutcome, Kill,
+};
 
 #[derive(Clone)]
 pub struct Target {
     object: Option<Object>,
-    position: Vec2,
+    movement: Movement,
+    /// Distance the target got pushed away from its movement path.
+    offset: Vec2,
     hp: f32,
+    statuses: Statuses,
+    /// Seconds the target moved for, slowing down with its speed.
+    time: f32,
 
-    last_hit: Instant,
-    last_shot: Instant,
+    last_hit: GameInstant,
+    /// Seconds since the last shot, slowing down with its speed.
+    shot_timer: f32,
+    fire_interval: Duration,
+    pattern: FirePattern,
+    /// Shots left in the current burst.
+    burst: u32,
+    drops: DropTable,
     sounds: Sounds,
 }
 
+/// Seconds between the shots of a burst.
+const BURST_DELAY: f32 = 0.15;
+
 impl Target {
-    pub fn new(layer: &Arc<Layer>, sounds: Sounds) -> Result<Self> {
+    /// Creates a target flying in to a random position unless the spawn says otherwise.
+    pub fn new(
+        layer: &Arc<Layer>,
+        sounds: Sounds,
+        difficulty: Difficulty,
+        spawn: &Spawn,
+    ) -> Result<Self> {
         let material = load_material(&asset("textures/enemies/target/target.png")?, 2);
         let appearance = Appearance::new()
             .model(Some(Model::Square))
             .material(material)
             .auto_scaled(HEIGHT)?;
 
-        let position: Vec2 = (rand::random::<Vec2>() + vec2(2.0, -0.7)) * vec2(1.0, 1.2);
-        let transform = Transform::default().position(position + vec2(6.0, 0.0));
+        let anchor = (random::<Vec2>() + vec2(2.0, -0.7)) * vec2(1.0, 1.2);
+        let movement = spawn.movement(anchor, "enter_right", "bob")?;
+        let transform = Transform::default().position(movement.position(0.0));
 
         let sizex = appearance.get_transform().size.x;
 
@@ -51,102 +79,199 @@ impl Target {
 
         Ok(Self {
             object,
-            position,
-            hp: 2.0,
-            last_hit: Instant::now(),
-            last_shot: Instant::now(),
+            movement,
+            offset: Vec2::ZERO,
+            hp: spawn.hp.unwrap_or(2.0),
+            // Wood burns well, but does not bleed.
+            statuses: Statuses::new(
+                Resistances::default()
+                    .with(StatusKind::Burn, 1.5)
+                    .with(StatusKind::Bleed, 0.0),
+            ),
+            time: 0.0,
+            last_hit: GameInstant::now(),
+            shot_timer: 0.0,
+            fire_interval: difficulty.fire_interval(Duration::from_secs(5)),
+            pattern: spawn.pattern.unwrap_or(FirePattern::Random),
+            burst: 0,
+            drops: spawn.drops.clone().unwrap_or_default(),
             sounds,
         })
     }
 
     /// Animates the target objects.
-    fn animate(&mut self) {
-        let time = TIME.time() as f32;
-        let object = self.object.as_mut().unwrap();
+    fn animate(&mut self, speed: f32, push: Vec2) {
+        self.time += TIME.delta_time() as f32 * speed;
+        self.offset += push;
+        self.object.as_mut().unwrap().transform.position =
+            self.movement.position(self.time) + self.offset;
+    }
 
-        object.transform.position.y = self.position.y + time.cos() * 0.05;
-        object.transform.position.x = object
-            .transform
-            .position
-            .lerp(self.position, TIME.delta_time() as f32)
-            .x;
+    fn take_damage(&mut self, damage: f32) {
+        self.hp -= damage;
+        if self.hp <= 1.0 {
+            self.object
+                .as_mut()
+                .unwrap()
+                .appearance
+                .set_layer(1)
+                .unwrap();
+        }
     }
-}
 
-impl Enemy for Target {
-    fn damage_if_id_right(&mut self, ids: &[usize], damage: f32) -> (f32, bool) {
-        let mut hit = false;
-        for id in ids {
-            if id == self.object.as_ref().unwrap().id() {
-                self.sounds.target_hit.play().unwrap();
-                self.hp -= damage;
-                self.last_hit = Instant::now();
-                hit = true;
+    /// The next shot of the firing pattern.
+    fn shoot(&mut self) -> EnemyMessage {
+        let own_position = self.object.as_ref().unwrap().transform.position;
+        let (position, direction) = match self.pattern {
+            FirePattern::Random => {
+                let position = vec2(5.0, random::<f32>() - 0.5);
+                let target = vec2(0.0, (random::<f32>() - 0.5) * 2.0);
+                (position, (target - position).normalize())
             }
-
-            if self.hp <= 1.0 {
-                self.object
-                    .as_mut()
-                    .unwrap()
-                    .appearance
-                    .set_layer(1)
-                    .unwrap();
+            FirePattern::Straight => (own_position, Vec2::NEG_X),
+            FirePattern::Burst { count, spread } => {
+                if self.burst == 0 {
+                    self.burst = count.max(1);
+                }
+                let shot = count.max(1) - self.burst;
+                self.burst -= 1;
+                let angle = if count > 1 {
+                    spread * (shot as f32 / (count - 1) as f32 - 0.5)
+                } else {
+                    0.0
+                };
+                (own_position, Vec2::from_angle(angle).rotate(Vec2::NEG_X))
             }
+        };
+        EnemyMessage::Shoot {
+            projectile_type: ProjectileType::Square,
+            position,
+            direction,
+        }
+    }
+
+    /// The reward for killing this target.
+    fn kill(&self, score: u32) -> Kill {
+        Kill {
+            score,
+            drop: self.drops.roll(),
+        }
+    }
+}
+
+impl Enemy for Target {
+    fn id(&self) -> usize {
+        *self.object.as_ref().unwrap().id()
+    }
+    fn hit(&mut self, hit: &Hit) -> HitOutcome {
+        if hit.damage <= 0.0 {
+            return HitOutcome::Blocked;
+        }
+        self.sounds.target_hit.play().unwrap();
+        self.take_damage(hit.damage);
+        self.last_hit = GameInstant::now();
+        if self.hp <= 0.0 {
+            // Hard shots are worth double.
+            let multiplier = if hit.critical { 2 } else { 1 };
+            return HitOutcome::Killed(self.kill(hit.kind.score() * multiplier));
+        }
+        for effect in hit.effects {
+            self.statuses.apply(*effect);
         }
-        (self.hp, hit)
+        HitOutcome::Hit
     }
     fn update(&mut self) -> EnemyMessage {
-        self.animate();
+        let status = self.statuses.update();
+        self.animate(status.speed, status.push);
+        if self.movement.finished(self.time) {
+            return EnemyMessage::Escaped;
+        }
+        if status.damage > 0.0 {
+            self.take_damage(status.damage);
+            if self.hp <= 0.0 {
+                return EnemyMessage::Died(self.kill(100));
+            }
+        }
+        let tint = self.statuses.tint();
         let object = self.object.as_mut().unwrap();
 
         if self.last_hit.elapsed() < Duration::from_millis(200) {
-            object.appearance.set_color(Color::from_rgb(2.0, 2.0, 2.0));
+            let flash = if accessibility::get().reduce_flashes {
+                1.3
+            } else {
+                2.0
+            };
+            object
+                .appearance
+                .set_color(Color::from_rgb(flash, flash, flash));
         } else {
-            object.appearance.set_color(Color::from_rgb(1.0, 1.0, 1.0));
+            object
+                .appearance
+                .set_color(tint.unwrap_or(Color::from_rgb(1.0, 1.0, 1.0)));
         }
 
         object.sync().unwrap();
-        if self.last_shot.elapsed() > Duration::from_secs(5) {
-            self.last_shot = Instant::now();
-            let position = vec2(5.0, random::<f32>() - 0.5);
-            let target = vec2(0.0, (random::<f32>() - 0.5) * 2.0);
-            EnemyMessage::Shoot {
-                projectile_type: ProjectileType::Square,
-                direction: (target - position).normalize(),
-                position,
-            }
+        self.shot_timer += TIME.delta_time() as f32 * status.speed;
+        let interval = if self.burst > 0 {
+            BURST_DELAY
+        } else {
+            self.fire_interval.as_secs_f32()
+        };
+        if self.shot_timer > interval {
+            self.shot_timer = 0.0;
+            self.shoot()
         } else {
             EnemyMessage::None
         }
     }
+    fn position(&self) -> Vec2 {
+        self.object.as_ref().unwrap().transform.position
+    }
+    fn set_sounds_paused(&self, paused: bool) {
+        self.sounds.set_paused(paused);
+    }
     fn remove(&mut self) {
+        let _ = std::mem::take(&mut self.object).unwrap().remove();
+    }
+    fn destroy(&mut self, particles: &mut ParticleSystem) {
         self.sounds.target_destroy.play().unwrap();
-        let object = self.object.as_mut().unwrap();
-        for _ in 0..3 {
-            debris_particle(
-                object.layer(),
-                TARGETDEBRIS.clone(),
-                object.transform.position,
-                random::<Vec2>() - vec2(0.5, 0.0),
-            );
+        let position = self.object.as_ref().unwrap().transform.position;
+        let _ = particles.emit(&TARGETDEBRIS, position);
+        self.remove();
+    }
+    fn debug_info(&self) -> DebugInfo {
+        let object = self.object.as_ref().unwrap();
+        DebugInfo {
+            id: Some(*object.id()),
+            layer: object.layer().clone(),
+            position: object.transform.position,
+            shape: ColliderShape::Circle(object.appearance.get_transform().size.x),
+            velocity: None,
         }
-        let _ = std::mem::take(&mut self.object).unwrap().remove();
     }
 }
 
-static TARGETDEBRIS: Lazy<Appearance> = Lazy::new(|| {
-    Appearance::new_instanced(
-        Some(Model::Square),
-        Some(Material::new_default_textured_instance(
-            &Texture::from_bytes(
-                &asset("textures/enemies/target/gib.png").unwrap(),
-                ImageFormat::Png,
-                1,
-                TextureSettings::default().srgb(true).sampler(SAMPLER),
-            )
-            .unwrap(),
-        )),
+static TARGETDEBRIS: Lazy<Emitter> = Lazy::new(|| {
+    Emitter::new(
+        Appearance::new_instanced(
+            Some(Model::Square),
+            Some(Material::new_default_textured_instance(
+                &Texture::from_bytes(
+                    &asset("textures/enemies/target/gib.png").unwrap(),
+                    ImageFormat::Png,
+                    1,
+                    TextureSettings::default().srgb(true).sampler(SAMPLER),
+                )
+                .unwrap(),
+            )),
+        )
+        .auto_scaled(HEIGHT)
+        .unwrap(),
     )
-    .auto_scaled(HEIGHT)
-    .unwrap()
+    .count(3)
+    .lifetime(3.0)
+    .velocity(vec2(-0.5, -0.7), vec2(0.5, 0.3))
+    .gravity(vec2(0.0, 0.7))
+    .angular_velocity(PI * 0.5)
+    .rotation_snap(PI * 0.5)
 });
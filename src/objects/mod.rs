@@ -1,17 +1,25 @@
 use std::sync::Arc;
 
-use crate::game::{GameSettings, Message};
+use crate::game::{accessibility, GameSettings, Message};
 
 use super::game::Layers;
 use anyhow::Result;
 use let_engine::prelude::*;
 use rand::random;
 
+pub mod abilities;
 pub mod button;
-pub mod fade;
+pub mod clock;
+pub mod debug;
+pub mod dialogue;
 pub mod framerate_counter;
 pub mod paddle;
+pub mod powerups;
 pub mod settings;
+pub mod titles;
+pub mod trajectory;
+pub mod transition;
+pub mod tween;
 
 pub mod enemies;
 pub mod particles;
@@ -38,8 +46,11 @@ impl Camera {
         })
     }
 
+    /// Shakes the camera for a tenth of a second scaled by the screen shake option.
     pub fn shake(&mut self) {
-        self.shaking = TIME.fps() as f32 * 0.1;
+        if accessibility::get().screen_shake > 0.0 {
+            self.shaking = TIME.fps() as f32 * 0.1;
+        }
     }
 
     pub fn update(&mut self) {
@@ -49,8 +60,11 @@ impl Camera {
             self.object.transform.position.x = scaling.x * 0.5;
 
             if self.shaking > 0.0 {
-                self.object.transform.position.x += random::<f32>() * 0.05;
-                self.object.layer().set_zoom(1.0 + random::<f32>() * 0.02)
+                let strength = accessibility::get().screen_shake;
+                self.object.transform.position.x += random::<f32>() * 0.05 * strength;
+                self.object
+                    .layer()
+                    .set_zoom(1.0 + random::<f32>() * 0.02 * strength)
             }
             self.shaking -= 1.0;
 
@@ -78,6 +92,17 @@ impl Objects {
         self.settings.update()
     }
 
+    /// Rebuilds the settings panel in the current language, keeping it open if it was.
+    pub fn rebuild_settings(&mut self, layers: &Layers, settings: GameSettings) -> Result<()> {
+        let visible = self.settings.is_visible();
+        let panel = settings::Settings::new(layers, settings)?;
+        std::mem::replace(&mut self.settings, panel).remove();
+        if visible {
+            self.settings.show(true);
+        }
+        Ok(())
+    }
+
     /// Updates all tick updated objects.
     pub fn tick_update(&mut self) {
         self.framerate_display.update();
@@ -1,14 +1,18 @@
-use std::{
-    f32::consts::PI,
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{f32::consts::PI, sync::Arc, time::Duration};
 
 use anyhow::Result;
 /// The player seen on the left side of the screen.
 use let_engine::prelude::*;
 
-use crate::HEIGHT;
+use crate::{
+    game::{accessibility, difficulty::Difficulty},
+    HEIGHT,
+};
+
+use super::{
+    clock::GameInstant,
+    debug::{ColliderShape, DebugInfo},
+};
 
 #[derive(Clone, Debug)]
 pub struct Paddle {
@@ -16,6 +20,10 @@ pub struct Paddle {
     pub max_health: f32,
 
     pub delta: Vec2,
+    /// Multiplier of the rotation speed of the arrow.
+    pub arrow_speed: f32,
+    /// Multiplier of the movement speed.
+    pub speed: f32,
     pub object: Object,
     pub arrow: Object,
     pub cursor: Object,
@@ -23,13 +31,18 @@ pub struct Paddle {
     pub body: Object,
     pub health_bar: Object,
 
-    grace_pediod: Instant,
+    grace_pediod: GameInstant,
+    /// How long the paddle is invincible after taking damage.
+    grace_duration: Duration,
+    /// Size of the body without any stretching.
+    size: Vec2,
+    height: f32,
 }
 
 const ARROW: ([Vertex; 2], [u32; 2]) = ([vert(0.0, -0.1), vert(0.0, -4.0)], [0, 1]);
 
 impl Paddle {
-    pub fn new(layer: &Arc<Layer>) -> Result<Self> {
+    pub fn new(layer: &Arc<Layer>, difficulty: Difficulty) -> Result<Self> {
         let mut object = NewObject::new();
         let mut body = NewObject::new();
         let mut health_bar = NewObject::new();
@@ -71,7 +84,7 @@ impl Paddle {
         health_bar.appearance = Appearance::new()
             .model(Some(Model::Square))
             .transform(Transform::default().size(size - 0.0156))
-            .color(Color::from_rgb(0.49, 0.886, 0.643));
+            .color(accessibility::get().palette.health(1.0));
 
         let arrow_model = Model::Custom(ModelData::new(Data::Fixed {
             vertices: &ARROW.0,
@@ -108,21 +121,47 @@ impl Paddle {
         let body = body.init_with_parent(&object)?;
         let cursor = cursor.init_with_parent(&object)?;
         Ok(Self {
-            health: 3.0,
-            max_health: 3.0,
+            health: difficulty.max_health(),
+            max_health: difficulty.max_health(),
             delta: Vec2::ZERO,
+            arrow_speed: 1.0,
+            speed: 1.0,
             object,
             arrow,
             cursor,
             body,
             health_bar,
-            grace_pediod: Instant::now(),
+            grace_pediod: GameInstant::now(),
+            grace_duration: difficulty.grace_period(),
+            size,
+            height: 1.0,
         })
     }
 
+    /// Size of the hitbox including stretching.
+    pub fn hitbox(&self) -> Vec2 {
+        self.size * vec2(1.0, self.height)
+    }
+
+    /// Stretches the paddle vertically by `height` including its hitbox.
+    pub fn set_height(&mut self, height: f32) -> Result<()> {
+        if self.height == height {
+            return Ok(());
+        }
+        self.height = height;
+        let size = self.size * vec2(1.0, height);
+        self.body.appearance.get_transform_mut().size = size;
+        self.object.set_collider(Some(
+            ColliderBuilder::new(Shape::square(size.x, size.y)).build(),
+        ));
+        self.body.sync()?;
+        self.object.sync()?;
+        Ok(())
+    }
+
     pub fn damage(&mut self, damage: f32) {
-        if self.grace_pediod.elapsed() > Duration::from_secs(1) {
-            self.grace_pediod = Instant::now();
+        if self.grace_pediod.elapsed() > self.grace_duration {
+            self.grace_pediod = GameInstant::now();
             self.health -= damage;
         }
     }
@@ -149,27 +188,30 @@ impl Paddle {
         let object = &mut self.object;
 
         object.transform.position = (object.transform.position
-            + vec2(0.0, position.y) * delta_time * 6.0)
+            + vec2(0.0, position.y) * delta_time * 6.0 * self.speed)
             .clamp(vec2(0.0, -0.9), vec2(1.0, 0.9));
 
         let rotation = &mut self.arrow.transform.rotation;
-        *rotation -= (INPUT.mouse_down(&MouseButton::Left) as u8) as f32 * delta_time;
-        *rotation += (INPUT.mouse_down(&MouseButton::Right) as u8) as f32 * delta_time;
+        let arrow_speed = delta_time * self.arrow_speed;
+        *rotation -= (INPUT.mouse_down(&MouseButton::Left) as u8) as f32 * arrow_speed;
+        *rotation += (INPUT.mouse_down(&MouseButton::Right) as u8) as f32 * arrow_speed;
         *rotation = rotation.clamp(PI * 0.25, PI * 0.75);
 
         let body_size = self.body.appearance.get_transform().size - 0.0156;
         let size = body_size * vec2(1.0, self.health / self.max_health);
         self.health_bar.appearance.get_transform_mut().size = size;
         self.health_bar.appearance.get_transform_mut().position.y = -size.y + body_size.y;
+        let accessibility = accessibility::get();
         self.health_bar
             .appearance
-            .set_color(Color::from_rgb(0.5, 0.04, 0.05).lerp(
-                Color::from_rgb(0.49, 0.886, 0.643),
-                self.health / self.max_health,
-            ));
+            .set_color(accessibility.palette.health(self.health / self.max_health));
 
-        if self.grace_pediod.elapsed() > Duration::from_secs(1) {
+        if self.grace_pediod.elapsed() > self.grace_duration {
             self.body.appearance.set_color(Color::WHITE);
+        } else if accessibility.reduce_flashes {
+            self.body
+                .appearance
+                .set_color(Color::from_rgb(1.2, 0.85, 0.8));
         } else {
             self.body
                 .appearance
@@ -182,6 +224,16 @@ impl Paddle {
         self.health_bar.sync().unwrap();
     }
 
+    pub fn debug_info(&self) -> DebugInfo {
+        DebugInfo {
+            id: Some(*self.object.id()),
+            layer: self.object.layer().clone(),
+            position: self.object.transform.position,
+            shape: ColliderShape::Square(self.body.appearance.get_transform().size),
+            velocity: None,
+        }
+    }
+
     pub fn unload(self) {
         self.object.remove().unwrap();
     }
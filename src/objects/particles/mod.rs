@@ -1,27 +1,214 @@
+use anyhow::Result;
 use let_engine::prelude::*;
-use std::{f32::consts::PI, sync::Arc, thread, time::Duration};
+use rand::random;
+use std::sync::Arc;
 
-pub fn debris_particle(
-    layer: &Arc<Layer>,
+/// The most hidden particle objects kept around for reuse.
+const POOL_LIMIT: usize = 256;
+
+/// Describes how a burst of particles gets spawned and how they behave over their life.
+#[derive(Clone, Debug)]
+pub struct Emitter {
     appearance: Appearance,
-    position: Vec2,
-    mut direction: Vec2,
-) {
-    let mut object = NewObjectBuilder::default()
-        .appearance(appearance)
-        .transform(Transform::default().position(position))
-        .build()
-        .unwrap()
-        .init(layer)
-        .unwrap();
-    thread::spawn(move || {
-        for i in 0..3000 {
-            thread::sleep(Duration::from_millis(1));
-            object.transform.position += (direction - vec2(0.0, 0.7)) * 0.001;
-            direction.y += 0.0007;
-            object.transform.rotation = (PI * 0.5) * (i as f32 * 0.001).round();
-            object.sync().unwrap();
+    count: usize,
+    lifetime: f32,
+    velocity: (Vec2, Vec2),
+    gravity: Vec2,
+    angular_velocity: f32,
+    rotation_snap: f32,
+    color: (Color, Color),
+    alpha: (f32, f32),
+}
+
+impl Emitter {
+    pub fn new(appearance: Appearance) -> Self {
+        Self {
+            appearance,
+            count: 1,
+            lifetime: 1.0,
+            velocity: (Vec2::ZERO, Vec2::ZERO),
+            gravity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            rotation_snap: 0.0,
+            color: (Color::WHITE, Color::WHITE),
+            alpha: (1.0, 1.0),
+        }
+    }
+
+    /// The amount of particles spawned per emission.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// How many seconds each particle lives.
+    pub fn lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// The starting velocity gets randomly chosen between `min` and `max`.
+    pub fn velocity(mut self, min: Vec2, max: Vec2) -> Self {
+        self.velocity = (min, max);
+        self
+    }
+
+    pub fn gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Rotation speed in radians per second.
+    pub fn angular_velocity(mut self, angular_velocity: f32) -> Self {
+        self.angular_velocity = angular_velocity;
+        self
+    }
+
+    /// Rounds the displayed rotation to multiples of `snap` radians. 0 disables snapping.
+    pub fn rotation_snap(mut self, snap: f32) -> Self {
+        self.rotation_snap = snap;
+        self
+    }
+
+    /// The color particles start with and fade to at the end of their life.
+    pub fn color(mut self, start: Color, end: Color) -> Self {
+        self.color = (start, end);
+        self
+    }
+
+    /// The alpha particles start with and fade to at the end of their life.
+    pub fn alpha(mut self, start: f32, end: f32) -> Self {
+        self.alpha = (start, end);
+        self
+    }
+}
+
+struct Particle {
+    object: Object,
+    velocity: Vec2,
+    gravity: Vec2,
+    rotation: f32,
+    angular_velocity: f32,
+    rotation_snap: f32,
+    color: (Color, Color),
+    alpha: (f32, f32),
+    age: f32,
+    lifetime: f32,
+}
+
+/// Simulates all particles of a layer in the game loop.
+pub struct ParticleSystem {
+    layer: Arc<Layer>,
+    particles: Vec<Particle>,
+    pool: Vec<Object>,
+}
+
+impl ParticleSystem {
+    pub fn new(layer: &Arc<Layer>) -> Self {
+        Self {
+            layer: layer.clone(),
+            particles: vec![],
+            pool: vec![],
         }
-        object.remove()
-    });
+    }
+
+    /// Spawns a burst of particles at the given position.
+    pub fn emit(&mut self, emitter: &Emitter, position: Vec2) -> Result<()> {
+        for _ in 0..emitter.count {
+            let (min, max) = emitter.velocity;
+            let velocity = min + random::<Vec2>() * (max - min);
+
+            let transform = Transform::default().position(position);
+            let object = if let Some(mut object) = self.pool.pop() {
+                object.transform = transform;
+                object.appearance = emitter.appearance.clone();
+                object
+            } else {
+                NewObjectBuilder::default()
+                    .appearance(emitter.appearance.clone())
+                    .transform(transform)
+                    .build()?
+                    .init(&self.layer)?
+            };
+
+            let mut particle = Particle {
+                object,
+                velocity,
+                gravity: emitter.gravity,
+                rotation: 0.0,
+                angular_velocity: emitter.angular_velocity,
+                rotation_snap: emitter.rotation_snap,
+                color: emitter.color,
+                alpha: emitter.alpha,
+                age: 0.0,
+                lifetime: emitter.lifetime,
+            };
+            particle.apply_appearance();
+            particle.object.sync()?;
+            self.particles.push(particle);
+        }
+        Ok(())
+    }
+
+    /// Moves all particles and recycles the ones that died.
+    pub fn update(&mut self) -> Result<()> {
+        let delta_time = TIME.delta_time() as f32;
+
+        let mut i = 0;
+        while i < self.particles.len() {
+            let particle = &mut self.particles[i];
+            particle.age += delta_time;
+
+            if particle.age >= particle.lifetime {
+                let mut object = self.particles.swap_remove(i).object;
+                if self.pool.len() < POOL_LIMIT {
+                    object.appearance.set_visible(false);
+                    object.sync()?;
+                    self.pool.push(object);
+                } else {
+                    let _ = object.remove();
+                }
+                continue;
+            }
+
+            particle.velocity += particle.gravity * delta_time;
+            particle.object.transform.position += particle.velocity * delta_time;
+            particle.rotation += particle.angular_velocity * delta_time;
+            particle.apply_appearance();
+            particle.object.sync()?;
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// The amount of particles that are alive.
+    pub fn count(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn remove(self) {
+        for particle in self.particles {
+            let _ = particle.object.remove();
+        }
+        for object in self.pool {
+            let _ = object.remove();
+        }
+    }
+}
+
+impl Particle {
+    /// Applies rotation, color and alpha for the current age.
+    fn apply_appearance(&mut self) {
+        let life = (self.age / self.lifetime).clamp(0.0, 1.0);
+
+        self.object.transform.rotation = if self.rotation_snap > 0.0 {
+            (self.rotation / self.rotation_snap).round() * self.rotation_snap
+        } else {
+            self.rotation
+        };
+
+        let mut color = self.color.0.lerp(self.color.1, life);
+        color.set_a(self.alpha.0 + (self.alpha.1 - self.alpha.0) * life);
+        self.object.appearance.set_color(color);
+    }
 }
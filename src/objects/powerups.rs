@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{
+    accessibility,
+    layout::{self, Anchor},
+    locale,
+};
+
+/// Chance of a killed enemy dropping a power-up.
+pub const DROP_CHANCE: f32 = 0.25;
+
+const PICKUP_SIZE: Vec2 = vec2(0.03, 0.03);
+/// Speed the pickups drift towards the paddle with.
+const PICKUP_SPEED: f32 = 0.3;
+/// Moves the indicator below the framerate counter in the same corner.
+const INDICATOR_OFFSET: Vec2 = vec2(0.0, 0.1);
+
+/// Effects the paddle gets by collecting a pickup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PowerUpKind {
+    /// Raises the maximum health by one and heals it.
+    Health,
+    /// Blocks the next hit.
+    Shield,
+    /// Makes the paddle taller.
+    Wide,
+    /// Rotates the arrow faster.
+    FastArrow,
+    /// Splits returned shots into three.
+    Split,
+    /// Slows down the whole game.
+    SlowMotion,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 6] = [
+        PowerUpKind::Health,
+        PowerUpKind::Shield,
+        PowerUpKind::Wide,
+        PowerUpKind::FastArrow,
+        PowerUpKind::Split,
+        PowerUpKind::SlowMotion,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUpKind::Health => "Health",
+            PowerUpKind::Shield => "Shield",
+            PowerUpKind::Wide => "Wide",
+            PowerUpKind::FastArrow => "Fast arrow",
+            PowerUpKind::Split => "Split",
+            PowerUpKind::SlowMotion => "Slow-mo",
+        }
+    }
+
+    /// The localization key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            PowerUpKind::Health => "powerup.health",
+            PowerUpKind::Shield => "powerup.shield",
+            PowerUpKind::Wide => "powerup.wide",
+            PowerUpKind::FastArrow => "powerup.fast_arrow",
+            PowerUpKind::Split => "powerup.split",
+            PowerUpKind::SlowMotion => "powerup.slow_motion",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            PowerUpKind::Health => Color::from_rgb(0.49, 0.886, 0.643),
+            PowerUpKind::Shield => Color::from_rgb(0.3, 0.6, 1.0),
+            PowerUpKind::Wide => Color::from_rgb(1.0, 0.8, 0.2),
+            PowerUpKind::FastArrow => Color::from_rgb(1.0, 0.3, 0.3),
+            PowerUpKind::Split => Color::from_rgb(0.8, 0.4, 1.0),
+            PowerUpKind::SlowMotion => Color::from_rgb(0.9, 0.9, 0.9),
+        }
+    }
+
+    /// Seconds of real time the effect lasts or `None` if it is applied instantly.
+    pub fn duration(&self) -> Option<f32> {
+        match self {
+            PowerUpKind::Health => None,
+            PowerUpKind::Shield => Some(15.0),
+            PowerUpKind::Wide => Some(12.0),
+            PowerUpKind::FastArrow => Some(12.0),
+            PowerUpKind::Split => Some(10.0),
+            PowerUpKind::SlowMotion => Some(4.0),
+        }
+    }
+}
+
+/// A power-up dropped by an enemy floating towards the paddle.
+pub struct Pickup {
+    pub kind: PowerUpKind,
+    object: Object,
+}
+
+impl Pickup {
+    pub fn new(layer: &Arc<Layer>, kind: PowerUpKind, position: Vec2) -> Result<Self> {
+        let object = NewObjectBuilder::default()
+            .appearance(
+                Appearance::new()
+                    .model(Some(Model::Square))
+                    .transform(Transform::default().size(PICKUP_SIZE))
+                    .color(kind.color()),
+            )
+            .transform(Transform::default().position(position))
+            .build()?
+            .init(layer)?;
+        Ok(Self { kind, object })
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let delta_time = TIME.delta_time() as f32;
+        self.object.transform.position.x -= PICKUP_SPEED * delta_time;
+        self.object.transform.rotation += delta_time * 2.0;
+        self.object.sync()?;
+        Ok(())
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.object.transform.position
+    }
+
+    /// IDs of all objects this pickup is touching.
+    pub fn touching(&self) -> Vec<usize> {
+        self.object.layer().intersections_with_shape(
+            Shape::square(PICKUP_SIZE.x, PICKUP_SIZE.y),
+            self.object.transform.into(),
+        )
+    }
+
+    pub fn remove(self) {
+        let _ = self.object.remove();
+    }
+}
+
+/// The effects currently active on the paddle and their indicator.
+pub struct PowerUps {
+    /// Active effects with their remaining seconds.
+    active: Vec<(PowerUpKind, f32)>,
+    indicator: Label<Object>,
+}
+
+impl PowerUps {
+    pub fn new(layer: &Arc<Layer>) -> Result<Self> {
+        let indicator = Label::new(
+            locale::font(),
+            LabelCreateInfo::default()
+                .align(Anchor::TopLeft.align())
+                .scale(accessibility::text_size(vec2(35.0, 35.0)))
+                .appearance(
+                    Appearance::default()
+                        .transform(layout::label_transform(INDICATOR_OFFSET))
+                        .color(Color::BLACK),
+                ),
+        )
+        .init(layer)?;
+        Ok(Self {
+            active: vec![],
+            indicator,
+        })
+    }
+
+    /// Starts the effect or refreshes its timer if it is already active.
+    pub fn activate(&mut self, kind: PowerUpKind) {
+        let Some(duration) = kind.duration() else {
+            return;
+        };
+        match self.active.iter_mut().find(|(active, _)| *active == kind) {
+            Some((_, remaining)) => *remaining = duration,
+            None => self.active.push((kind, duration)),
+        }
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|(active, _)| *active == kind)
+    }
+
+    /// Ends the effect early returning whether it was active.
+    pub fn consume(&mut self, kind: PowerUpKind) -> bool {
+        let len = self.active.len();
+        self.active.retain(|(active, _)| *active != kind);
+        len != self.active.len()
+    }
+
+    /// Counts down the timers and updates the indicator.
+    ///
+    /// Timers run on real time, so slow motion lasts as long as it says.
+    pub fn update(&mut self) {
+        let delta_time = if TIME.scale() > 0.0 {
+            (TIME.delta_time() / TIME.scale()) as f32
+        } else {
+            0.0
+        };
+        self.active.retain_mut(|(_, remaining)| {
+            *remaining -= delta_time;
+            *remaining > 0.0
+        });
+
+        let scale = if self.is_active(PowerUpKind::SlowMotion) {
+            0.5
+        } else {
+            1.0
+        };
+        // Resuming from the pause menu resets the scale, so it gets applied every frame.
+        if TIME.scale() != scale {
+            TIME.set_scale(scale);
+        }
+
+        let text = self
+            .active
+            .iter()
+            .map(|(kind, remaining)| format!("{} {}s", locale::text(kind.key()), remaining.ceil()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.indicator.text != text {
+            self.indicator.update_text(text);
+        }
+        self.indicator
+            .object
+            .appearance
+            .set_transform(layout::label_transform(INDICATOR_OFFSET));
+        self.indicator.sync();
+    }
+
+    pub fn remove(self) {
+        let _ = self.indicator.object.remove();
+    }
+}
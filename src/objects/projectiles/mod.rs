@@ -1,10 +1,19 @@
-use std::{sync::Arc, time::Instant};
+use std::sync::Arc;
 
 use anyhow::Result;
 use let_engine::prelude::*;
 use once_cell::sync::Lazy;
 
-use crate::game::sounds::Sounds;
+use crate::game::{
+    accessibility::{self, Palette},
+    sounds::Sounds,
+};
+
+use super::{
+    clock::GameInstant,
+    debug::{ColliderShape, DebugInfo},
+    enemies::status::StatusEffect,
+};
 
 pub trait Projectile: Send + Sync {
     fn update(&mut self); // -> ProjectileMessage;
@@ -14,8 +23,22 @@ pub trait Projectile: Send + Sync {
     fn friendly(&self) -> bool;
     fn damage(&self) -> f32;
     fn damage_multiplier(&mut self, multiplier: f32);
-    fn age(&self) -> Instant;
+    fn kind(&self) -> ProjectileType;
+    /// Whether this got returned with a hard shot.
+    fn critical(&self) -> bool;
+    /// Marks this as returned with a hard shot, adding the effects of hard shots.
+    fn set_critical(&mut self, critical: bool);
+    fn age(&self) -> GameInstant;
+    /// Status effects applied to the enemies this hits.
+    fn effects(&self) -> &[StatusEffect];
+    /// Adds the effects of getting parried.
+    fn parry(&mut self);
+    /// A returned copy of this flying in the given direction with the same damage and effects.
+    fn split(&self, direction: Vec2) -> Result<Box<dyn Projectile>>;
+    /// Pauses or resumes the sound of the projectile.
+    fn set_sound_paused(&self, paused: bool);
     fn remove(&mut self);
+    fn debug_info(&self) -> DebugInfo;
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -24,11 +47,48 @@ pub enum ProjectileMessage {
     Hit(Vec<usize>),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ProjectileType {
     Square,
 }
 
 impl ProjectileType {
+    /// Effects projectiles of this type apply after getting parried.
+    pub fn parry_effects(&self) -> &'static [StatusEffect] {
+        match self {
+            Self::Square => &[
+                StatusEffect::Bleed {
+                    damage: 0.3,
+                    duration: 3.0,
+                },
+                StatusEffect::Slow {
+                    factor: 0.5,
+                    duration: 3.0,
+                },
+            ],
+        }
+    }
+
+    /// Effects projectiles of this type apply after getting returned with a hard shot.
+    pub fn hard_shot_effects(&self) -> &'static [StatusEffect] {
+        match self {
+            Self::Square => &[
+                StatusEffect::Stun(1.0),
+                StatusEffect::Burn {
+                    damage: 0.25,
+                    duration: 2.0,
+                },
+            ],
+        }
+    }
+
+    /// Score for killing an enemy with this projectile.
+    pub fn score(&self) -> u32 {
+        match self {
+            Self::Square => 100,
+        }
+    }
+
     pub fn spawn(
         &self,
         layer: &Arc<Layer>,
@@ -47,12 +107,20 @@ impl ProjectileType {
     }
 }
 
+/// Half extents of the shape used to check what a square projectile touches.
+const SQUARE_HITBOX: Vec2 = vec2(0.03, 0.03);
+
 #[derive(Clone)]
 pub struct Square {
     object: Option<Object>,
+    /// Black square behind the projectile with the high contrast option.
+    outline: Option<Object>,
+    palette: Palette,
     direction: Vec2,
-    age: Instant,
+    age: GameInstant,
+    effects: Vec<StatusEffect>,
     friendly: bool,
+    critical: bool,
     damage: f32,
     sound: Sound,
 }
@@ -62,10 +130,29 @@ static SQUARE: Lazy<Appearance> = Lazy::new(|| {
         .transform(Transform::default().size(vec2(0.02, 0.02)))
 });
 
+static SQUARE_OUTLINE: Lazy<Appearance> = Lazy::new(|| {
+    Appearance::new_instanced(Some(Model::Square), None)
+        .transform(Transform::default().size(vec2(0.03, 0.03)))
+        .color(Color::BLACK)
+});
+
 impl Square {
     pub fn new(layer: &Arc<Layer>, position: Vec2, direction: Vec2, sound: Sound) -> Result<Self> {
+        let accessibility = accessibility::get();
+        // Initialized first to be drawn behind the projectile.
+        let outline = accessibility
+            .high_contrast
+            .then(|| {
+                NewObjectBuilder::default()
+                    .appearance(SQUARE_OUTLINE.clone())
+                    .transform(Transform::default().position(position))
+                    .build()?
+                    .init(layer)
+            })
+            .transpose()?;
+
         let object = NewObjectBuilder::default()
-            .appearance(SQUARE.clone())
+            .appearance(SQUARE.clone().color(accessibility.palette.hostile()))
             .transform(Transform::default().position(position))
             .build()?;
 
@@ -73,15 +160,33 @@ impl Square {
 
         Ok(Self {
             object,
+            outline,
+            palette: accessibility.palette,
             direction,
-            age: Instant::now(),
+            age: GameInstant::now(),
+            effects: vec![],
             friendly: false,
+            critical: false,
             damage: 1.0,
             sound,
         })
     }
 }
 
+impl Square {
+    /// Colors the projectile by who shot it.
+    fn update_color(&mut self) {
+        let color = match (self.friendly, self.critical) {
+            (_, true) => self.palette.critical(),
+            (true, false) => self.palette.friendly(),
+            (false, false) => self.palette.hostile(),
+        };
+        if let Some(object) = self.object.as_mut() {
+            object.appearance.set_color(color);
+        }
+    }
+}
+
 impl Projectile for Square {
     fn update(&mut self) {
         // -> ProjectileMessage {
@@ -98,19 +203,27 @@ impl Projectile for Square {
 
         object.transform.position += self.direction * TIME.delta_time() as f32;
         object.sync().unwrap();
+        if let Some(outline) = self.outline.as_mut() {
+            outline.transform.position = object.transform.position;
+            outline.sync().unwrap();
+        }
     }
 
     fn rebound(&mut self, direction: Vec2) {
         self.sound.play().unwrap();
         self.friendly = true;
         self.direction = direction;
+        self.update_color();
+        // Returned squares push what they hit.
+        self.effects.push(StatusEffect::Knockback(direction * 0.5));
     }
 
     fn touching(&self) -> Vec<usize> {
         let object = self.object.as_ref().unwrap();
-        object
-            .layer()
-            .intersections_with_shape(Shape::square(0.03, 0.03), object.transform.into())
+        object.layer().intersections_with_shape(
+            Shape::square(SQUARE_HITBOX.x, SQUARE_HITBOX.y),
+            object.transform.into(),
+        )
     }
 
     fn position(&self) -> Vec2 {
@@ -125,15 +238,78 @@ impl Projectile for Square {
         self.damage *= multiplier;
     }
 
-    fn age(&self) -> Instant {
+    fn age(&self) -> GameInstant {
         self.age
     }
 
+    fn kind(&self) -> ProjectileType {
+        ProjectileType::Square
+    }
+
+    fn critical(&self) -> bool {
+        self.critical
+    }
+
+    fn set_critical(&mut self, critical: bool) {
+        if critical && !self.critical {
+            self.effects
+                .extend_from_slice(self.kind().hard_shot_effects());
+        }
+        self.critical = critical;
+        self.update_color();
+    }
+
+    fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    fn parry(&mut self) {
+        self.effects.extend_from_slice(self.kind().parry_effects());
+    }
+
+    fn split(&self, direction: Vec2) -> Result<Box<dyn Projectile>> {
+        let object = self.object.as_ref().unwrap();
+        let mut split = Square::new(
+            object.layer(),
+            object.transform.position,
+            direction,
+            self.sound.clone(),
+        )?;
+        split.friendly = true;
+        split.critical = self.critical;
+        split.damage = self.damage;
+        split.effects = self.effects.clone();
+        split.update_color();
+        Ok(Box::new(split))
+    }
+
+    fn set_sound_paused(&self, paused: bool) {
+        let _ = if paused {
+            self.sound.pause()
+        } else {
+            self.sound.resume()
+        };
+    }
+
     fn remove(&mut self) {
         let _ = self.object.take().unwrap().remove();
+        if let Some(outline) = self.outline.take() {
+            let _ = outline.remove();
+        }
     }
 
     fn friendly(&self) -> bool {
         self.friendly
     }
+
+    fn debug_info(&self) -> DebugInfo {
+        let object = self.object.as_ref().unwrap();
+        DebugInfo {
+            id: None,
+            layer: object.layer().clone(),
+            position: object.transform.position,
+            shape: ColliderShape::Square(SQUARE_HITBOX),
+            velocity: Some(self.direction),
+        }
+    }
 }
@@ -1,14 +1,106 @@
 use std::sync::Arc;
 
-use crate::{
-    game::{load_material, GameSettings, Layers, Message},
-    FONT_RAWR,
+use crate::game::{
+    accessibility::{self, Accessibility, Palette},
+    load_material, locale, GameSettings, Layers, Message,
 };
 use anyhow::Result;
 use let_engine::prelude::*;
 
 use super::button::Button;
 
+const SHAKE_STEPS: [f32; 5] = [0.0, 0.5, 1.0, 1.5, 2.0];
+const TEXT_SCALE_STEPS: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// The accessibility options that have a button in the settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AccessibilityOption {
+    Shake,
+    ReduceFlashes,
+    HighContrast,
+    Palette,
+    TextScale,
+}
+
+impl AccessibilityOption {
+    const ALL: [AccessibilityOption; 5] = [
+        AccessibilityOption::Shake,
+        AccessibilityOption::ReduceFlashes,
+        AccessibilityOption::HighContrast,
+        AccessibilityOption::Palette,
+        AccessibilityOption::TextScale,
+    ];
+
+    /// The text of the button showing the current value.
+    fn text(&self, accessibility: &Accessibility) -> String {
+        let switch = |on: bool| locale::text(if on { "settings.on" } else { "settings.off" });
+        let (key, value) = match self {
+            AccessibilityOption::Shake => (
+                "settings.shake",
+                format!("{}%", (accessibility.screen_shake * 100.0).round()),
+            ),
+            AccessibilityOption::ReduceFlashes => (
+                "settings.reduce_flashes",
+                switch(accessibility.reduce_flashes),
+            ),
+            AccessibilityOption::HighContrast => (
+                "settings.high_contrast",
+                switch(accessibility.high_contrast),
+            ),
+            AccessibilityOption::Palette => (
+                "settings.palette",
+                locale::text(&format!("palette.{}", accessibility.palette.name())),
+            ),
+            AccessibilityOption::TextScale => (
+                "settings.text_scale",
+                format!("{}%", (accessibility.text_scale * 100.0).round()),
+            ),
+        };
+        format!("{}: {value}", locale::text(key))
+    }
+
+    /// The options with this one switched to its next value.
+    fn next(&self, accessibility: Accessibility) -> Accessibility {
+        match self {
+            AccessibilityOption::Shake => Accessibility {
+                screen_shake: next_step(&SHAKE_STEPS, accessibility.screen_shake),
+                ..accessibility
+            },
+            AccessibilityOption::ReduceFlashes => Accessibility {
+                reduce_flashes: !accessibility.reduce_flashes,
+                ..accessibility
+            },
+            AccessibilityOption::HighContrast => Accessibility {
+                high_contrast: !accessibility.high_contrast,
+                ..accessibility
+            },
+            AccessibilityOption::Palette => {
+                let index = Palette::ALL
+                    .iter()
+                    .position(|palette| *palette == accessibility.palette)
+                    .unwrap_or(0);
+                Accessibility {
+                    palette: Palette::ALL[(index + 1) % Palette::ALL.len()],
+                    ..accessibility
+                }
+            }
+            AccessibilityOption::TextScale => Accessibility {
+                text_scale: next_step(&TEXT_SCALE_STEPS, accessibility.text_scale),
+                ..accessibility
+            },
+        }
+    }
+}
+
+/// The first step above the value, wrapping around to the smallest.
+fn next_step(steps: &[f32], value: f32) -> f32 {
+    steps
+        .iter()
+        .copied()
+        .find(|step| *step > value + 0.01)
+        .unwrap_or(steps[0])
+}
+
 #[derive(Clone, Debug)]
 pub struct Settings {
     visible: bool,
@@ -17,6 +109,8 @@ pub struct Settings {
     panel: Object,
     label: Label<Object>,
     settings_labels: Label<Object>,
+    language_button: Button,
+    accessibility_buttons: Vec<(AccessibilityOption, Button)>,
     back_button: Button,
 }
 
@@ -38,34 +132,46 @@ impl Settings {
         let transform = Transform::default().size(vec2(0.8, 0.73));
 
         let label = Label::new(
-            &FONT_RAWR,
+            locale::display_font(),
             LabelCreateInfo::default()
                 .appearance(Appearance::default())
                 .transform(transform)
-                .text("Settings")
-                .scale(vec2(80.0, 80.0)),
+                .text(locale::text("settings.title"))
+                .scale(accessibility::text_size(vec2(80.0, 80.0))),
         )
         .init_with_parent(&panel)?;
 
         let settings_labels = Label::new(
-            &FONT_RAWR,
+            locale::display_font(),
             LabelCreateInfo::default()
                 .appearance(Appearance::default())
                 .transform(Transform::default().size(panel.transform.size * vec2(0.92, 0.83)))
                 .align(Direction::W)
                 .text(
-                    "
-Vsync
-
-Fullscreen
-
-FPS limit
-                    ",
+                    [
+                        "settings.vsync",
+                        "settings.fullscreen",
+                        "settings.fps_limit",
+                    ]
+                    .map(locale::text)
+                    .join("\n\n"),
                 )
-                .scale(vec2(47.0, 47.0)),
+                .scale(accessibility::text_size(vec2(47.0, 47.0))),
         )
         .init_with_parent(&panel)?;
 
+        let language_button = option_button(&panel, &language_text(&settings), vec2(-0.5, 0.72))?;
+
+        let accessibility_buttons = AccessibilityOption::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let text = option.text(&settings.accessibility);
+                let position = vec2(0.45, -0.45 + index as f32 * 0.2);
+                Ok((option, option_button(&panel, &text, position)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         let back_button = Button::new(
             super::button::Parent::Object(&panel),
             load_material(&asset("textures/ui/back.png")?, 1),
@@ -79,6 +185,8 @@ FPS limit
             panel,
             label,
             settings_labels,
+            language_button,
+            accessibility_buttons,
             back_button,
             // fullscreen,
         })
@@ -97,11 +205,53 @@ FPS limit
         self.show(!self.visible);
     }
 
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Stores the applied settings and shows their values on the buttons.
+    pub fn set(&mut self, settings: GameSettings) {
+        self.settings = settings;
+        if let Some(text) = self.language_button.text.as_mut() {
+            text.update_text(language_text(&settings));
+        }
+        for (option, button) in &mut self.accessibility_buttons {
+            if let Some(text) = button.text.as_mut() {
+                text.update_text(option.text(&settings.accessibility));
+            }
+        }
+    }
+
+    pub fn remove(self) {
+        let _ = self.label.object.remove();
+        let _ = self.settings_labels.object.remove();
+        self.language_button.remove();
+        for (_, button) in self.accessibility_buttons {
+            button.remove();
+        }
+        self.back_button.remove();
+        let _ = self.panel.remove();
+    }
+
     pub fn update(&mut self) -> Option<Message> {
         let mut message = None;
         self.panel.sync().unwrap();
         self.label.sync();
         self.settings_labels.sync();
+        self.language_button.on_release(|| {
+            message = Some(Message::ApplySettings(GameSettings {
+                language: self.settings.language.next(),
+                ..self.settings
+            }))
+        });
+        for (option, button) in &mut self.accessibility_buttons {
+            button.on_release(|| {
+                message = Some(Message::ApplySettings(GameSettings {
+                    accessibility: option.next(self.settings.accessibility),
+                    ..self.settings
+                }))
+            });
+        }
         self.back_button
             .on_press(|| message = Some(Message::ShowSettings(false)));
 
@@ -128,11 +278,15 @@ impl GameMenu {
             .build()?
             .init(layer)?;
 
-        let resume = gm_button(&background, "Resume", vec2(0.0, 0.0))?;
+        let resume = gm_button(&background, &locale::text("pause.resume"), vec2(0.0, 0.0))?;
 
-        let options = gm_button(&background, "Options", vec2(0.0, 0.4))?;
+        let options = gm_button(&background, &locale::text("pause.options"), vec2(0.0, 0.4))?;
 
-        let main_menu = gm_button(&background, "Main Menu", vec2(0.0, 0.8))?;
+        let main_menu = gm_button(
+            &background,
+            &locale::text("pause.main_menu"),
+            vec2(0.0, 0.8),
+        )?;
 
         Ok(Self {
             background,
@@ -159,7 +313,20 @@ impl GameMenu {
 
     pub fn toggle(&mut self) {
         self.set_visible(!self.background.appearance.get_visible());
-        self.set_enabled(!self.resume.object.collider().unwrap().is_enabled());
+        self.set_enabled(!self.is_enabled());
+    }
+
+    /// Opens the menu if it is not open already.
+    pub fn pause(&mut self) {
+        if !self.is_enabled() {
+            self.set_visible(true);
+            self.set_enabled(true);
+        }
+    }
+
+    /// Whether the menu is open and the game paused.
+    pub fn is_enabled(&self) -> bool {
+        self.resume.object.collider().unwrap().is_enabled()
     }
 
     pub fn set_enabled(&mut self, enabled: bool) {
@@ -198,6 +365,30 @@ impl GameMenu {
     }
 }
 
+/// The text of the language button.
+fn language_text(settings: &GameSettings) -> String {
+    format!(
+        "{}: {}",
+        locale::text("settings.language"),
+        locale::text(&format!("language.{}", settings.language.code()))
+    )
+}
+
+/// A button of the settings panel that changes an option.
+fn option_button(panel: &Object, text: &str, position: Vec2) -> Result<Button> {
+    Button::new(
+        super::button::Parent::Object(panel),
+        load_material(&asset("textures/ui/button.png")?, 1),
+        Some(
+            LabelCreateInfo::default()
+                .text(text)
+                .align(Direction::Center)
+                .scale(accessibility::text_size(vec2(40.0, 40.0))),
+        ),
+        position,
+    )
+}
+
 fn gm_button(background: &Object, text: &str, position: Vec2) -> Result<Button> {
     Button::new(
         super::button::Parent::Object(background),
@@ -206,7 +397,7 @@ fn gm_button(background: &Object, text: &str, position: Vec2) -> Result<Button>
             LabelCreateInfo::default()
                 .text(text)
                 .align(Direction::Center)
-                .scale(vec2(60.0, 60.0)),
+                .scale(accessibility::text_size(vec2(60.0, 60.0))),
         ),
         position,
     )
@@ -0,0 +1,304 @@
+//! Title cards shown over the game, one after another or stacked.
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::Result;
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{accessibility, locale};
+
+use super::tween::{Easing, Sequence};
+
+const FADE_TIME: f32 = 1.0;
+/// Fade out time of titles pushed away by a replacing one.
+const REPLACE_FADE_TIME: f32 = 0.3;
+/// Distance between the lines of stacked titles.
+const STACK_SPACING: f32 = 0.15;
+/// Size of subtitles relative to their title.
+pub const SUBTITLE_SCALE: f32 = 0.6;
+
+/// A title with everything about how it is shown.
+///
+/// The text and subtitle get translated if they are localization keys.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TitleCard {
+    pub text: String,
+    /// Smaller text shown below the title.
+    pub subtitle: Option<String>,
+    pub color: Color,
+    pub size: Vec2,
+    pub position: TitlePosition,
+    /// Seconds the title is held between fading in and out.
+    pub duration: f32,
+    pub mode: TitleMode,
+}
+
+impl TitleCard {
+    pub fn new(text: impl Into<String>, color: Color, size: Vec2) -> Self {
+        Self {
+            text: text.into(),
+            subtitle: None,
+            color,
+            size,
+            position: TitlePosition::Top,
+            duration: 2.0,
+            mode: TitleMode::Queue,
+        }
+    }
+
+    pub fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn position(mut self, position: TitlePosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn mode(mut self, mode: TitleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// What happens to titles already shown when a new one comes in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TitleMode {
+    /// Waits until the titles before it are gone.
+    Queue,
+    /// Fades the shown titles out right away and drops the waiting ones.
+    Replace,
+    /// Shows up immediately below the titles already shown.
+    Stack,
+}
+
+impl TitleMode {
+    pub const ALL: [TitleMode; 3] = [TitleMode::Queue, TitleMode::Replace, TitleMode::Stack];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TitleMode::Queue => "queue",
+            TitleMode::Replace => "replace",
+            TitleMode::Stack => "stack",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TitlePosition {
+    Top,
+    Center,
+    Bottom,
+}
+
+impl TitlePosition {
+    pub const ALL: [TitlePosition; 3] = [
+        TitlePosition::Top,
+        TitlePosition::Center,
+        TitlePosition::Bottom,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TitlePosition::Top => "top",
+            TitlePosition::Center => "center",
+            TitlePosition::Bottom => "bottom",
+        }
+    }
+
+    fn align(&self) -> Direction {
+        match self {
+            TitlePosition::Top => Direction::N,
+            TitlePosition::Center => Direction::Center,
+            TitlePosition::Bottom => Direction::S,
+        }
+    }
+
+    /// The way further lines are stacked, away from the edge the title sticks to.
+    fn stack_direction(&self) -> f32 {
+        match self {
+            TitlePosition::Bottom => -1.0,
+            TitlePosition::Top | TitlePosition::Center => 1.0,
+        }
+    }
+}
+
+/// A title card being shown right now.
+struct ShownTitle {
+    position: TitlePosition,
+    /// Lines of stacked titles above this one.
+    lines: f32,
+    labels: Vec<Label<Object>>,
+    alpha: Sequence<f32>,
+    /// Set by the sequence once the title faded out.
+    done: Arc<AtomicBool>,
+}
+
+impl ShownTitle {
+    fn new(layer: &Arc<Layer>, card: TitleCard, lines: f32) -> Result<Self> {
+        let mut labels = vec![];
+        // Bigger text needs more space between the lines.
+        let spacing = STACK_SPACING * accessibility::get().text_scale;
+        let mut line = |text: &str, color: Color, scale: Vec2, offset: Vec2| -> Result<()> {
+            let position = vec2(0.0, lines * spacing * card.position.stack_direction());
+            let appearance = Appearance::new()
+                .color(color)
+                .transform(Transform::default().size(vec2(0.55, 0.7)));
+            labels.push(
+                Label::new(
+                    locale::font(),
+                    LabelCreateInfo::default()
+                        .text(text)
+                        .align(card.position.align())
+                        .transform(Transform::default().position(position + offset))
+                        .appearance(appearance)
+                        .scale(accessibility::text_size(scale)),
+                )
+                .init(layer)?,
+            );
+            Ok(())
+        };
+
+        // Every line gets a black copy behind it as a shadow.
+        let text = locale::text(&card.text);
+        line(&text, Color::BLACK, card.size, vec2(0.01, 0.01))?;
+        line(&text, card.color, card.size, Vec2::ZERO)?;
+        if let Some(subtitle) = card.subtitle.as_deref().map(locale::text) {
+            let offset = vec2(0.0, spacing * card.position.stack_direction());
+            let size = card.size * SUBTITLE_SCALE;
+            line(&subtitle, Color::BLACK, size, offset + vec2(0.01, 0.01))?;
+            line(&subtitle, Color::WHITE, size, offset)?;
+        }
+
+        let done = Arc::new(AtomicBool::new(false));
+        Ok(Self {
+            position: card.position,
+            lines: lines + if card.subtitle.is_some() { 2.0 } else { 1.0 },
+            labels,
+            alpha: Sequence::new(0.0)
+                .then(1.0, FADE_TIME, Easing::SineInOut)
+                .wait(card.duration)
+                .then(0.0, FADE_TIME, Easing::SineInOut)
+                .call(done_setter(&done)),
+            done,
+        })
+    }
+
+    fn fade_out(&mut self) {
+        self.alpha = Sequence::new(self.alpha.value())
+            .then(0.0, REPLACE_FADE_TIME, Easing::Linear)
+            .call(done_setter(&self.done));
+    }
+
+    fn done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    fn update(&mut self) -> Result<()> {
+        let alpha = self.alpha.update();
+        for label in &mut self.labels {
+            label.object.appearance.get_color_mut().set_a(alpha);
+            label.object.sync()?;
+            label.sync();
+        }
+        Ok(())
+    }
+
+    fn remove(self) {
+        for label in self.labels {
+            let _ = label.object.remove();
+        }
+    }
+}
+
+/// A callback marking the title as done.
+fn done_setter(done: &Arc<AtomicBool>) -> impl FnOnce() + Send + Sync + 'static {
+    let done = done.clone();
+    move || done.store(true, Ordering::Relaxed)
+}
+
+/// Shows title cards in the order they come in, driven by the game loop.
+pub struct Titles {
+    layer: Arc<Layer>,
+    shown: Vec<ShownTitle>,
+    queue: VecDeque<TitleCard>,
+}
+
+impl Titles {
+    pub fn new(layer: &Arc<Layer>) -> Self {
+        Self {
+            layer: layer.clone(),
+            shown: vec![],
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Shows the title or queues it up depending on its mode.
+    pub fn show(&mut self, card: TitleCard) -> Result<()> {
+        match card.mode {
+            TitleMode::Queue if !self.shown.is_empty() || !self.queue.is_empty() => {
+                self.queue.push_back(card);
+            }
+            TitleMode::Queue => self.start(card)?,
+            TitleMode::Replace => {
+                self.queue.clear();
+                for title in &mut self.shown {
+                    title.fade_out();
+                }
+                self.start(card)?;
+            }
+            TitleMode::Stack => {
+                let lines = self
+                    .shown
+                    .iter()
+                    .filter(|title| title.position == card.position)
+                    .map(|title| title.lines)
+                    .fold(0.0, f32::max);
+                self.shown.push(ShownTitle::new(&self.layer, card, lines)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, card: TitleCard) -> Result<()> {
+        self.shown.push(ShownTitle::new(&self.layer, card, 0.0)?);
+        Ok(())
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let mut index = 0;
+        while index < self.shown.len() {
+            let title = &mut self.shown[index];
+            title.update()?;
+            if title.done() {
+                self.shown.swap_remove(index).remove();
+            } else {
+                index += 1;
+            }
+        }
+        if self.shown.is_empty() {
+            if let Some(card) = self.queue.pop_front() {
+                self.start(card)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(self) {
+        for title in self.shown {
+            title.remove();
+        }
+    }
+}
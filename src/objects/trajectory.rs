@@ -0,0 +1,147 @@
+//! Preview of the path a returned shot takes.
+use std::{f32::consts::PI, sync::Arc};
+
+use anyhow::Result;
+use let_engine::prelude::*;
+
+use super::{
+    debug::{ColliderShape, Pool, CIRCLE_OUTLINE, LINE},
+    enemies::Enemy,
+    paddle::Paddle,
+};
+
+/// Height at which projectiles bounce off the top and bottom of the screen.
+const BOUNDS: f32 = 0.99;
+/// Stops simulating after this many bounces.
+const MAX_BOUNCES: usize = 8;
+/// Radians per second the aim assist turns the arrow.
+const ASSIST_SPEED: f32 = 0.4;
+
+fn path_color() -> Color {
+    Color::from_rgba(1.0, 1.0, 1.0, 0.35)
+}
+fn target_color() -> Color {
+    Color::from_rgba(1.0, 0.3, 0.2, 0.8)
+}
+
+/// Draws the bouncing path of a rebound and marks the enemy it would hit.
+pub struct Trajectory {
+    lines: Pool,
+    markers: Pool,
+}
+
+impl Trajectory {
+    pub fn new(layer: &Arc<Layer>) -> Result<Self> {
+        Ok(Self {
+            lines: Pool::new(layer, &LINE.0, &LINE.1)?,
+            markers: Pool::new(layer, &CIRCLE_OUTLINE.0, &CIRCLE_OUTLINE.1)?,
+        })
+    }
+
+    /// Simulates `length` seconds of a shot returned by the paddle right now.
+    pub fn update(
+        &mut self,
+        paddle: &Paddle,
+        enemies: &[Box<dyn Enemy>],
+        length: f32,
+    ) -> Result<()> {
+        let mut position = paddle.object.transform.position;
+        let mut velocity = paddle.rebound_direction();
+        let mut remaining = length;
+
+        for _ in 0..MAX_BOUNCES {
+            if remaining <= 0.0 {
+                break;
+            }
+            let wall_time = if velocity.y > 0.0 {
+                (BOUNDS - position.y) / velocity.y
+            } else if velocity.y < 0.0 {
+                (-BOUNDS - position.y) / velocity.y
+            } else {
+                f32::INFINITY
+            };
+            let time = wall_time.min(remaining);
+            let segment = velocity * time;
+
+            if let Some((distance, enemy)) = first_hit(position, segment, enemies) {
+                let end = segment.normalize_or_zero() * distance;
+                self.draw_line(position, end)?;
+                self.markers.draw(
+                    Transform::default().position(enemy.position()),
+                    Vec2::splat(radius(enemy) * 1.3),
+                    target_color(),
+                )?;
+                break;
+            }
+
+            self.draw_line(position, segment)?;
+            position += segment;
+            velocity.y = -velocity.y;
+            remaining -= time;
+        }
+
+        self.lines.finish()?;
+        self.markers.finish()
+    }
+
+    fn draw_line(&mut self, start: Vec2, vector: Vec2) -> Result<()> {
+        let transform = Transform::default()
+            .position(start)
+            .rotation(vector.y.atan2(vector.x));
+        self.lines
+            .draw(transform, Vec2::splat(vector.length()), path_color())
+    }
+
+    pub fn remove(self) {
+        self.lines.remove();
+        self.markers.remove();
+    }
+}
+
+/// The closest enemy the segment from `start` along `segment` passes through and the distance to it.
+fn first_hit(start: Vec2, segment: Vec2, enemies: &[Box<dyn Enemy>]) -> Option<(f32, &dyn Enemy)> {
+    let length = segment.length();
+    let direction = segment.normalize_or_zero();
+    enemies
+        .iter()
+        .filter_map(|enemy| {
+            let radius = radius(enemy.as_ref());
+            // Distance along the segment to the point closest to the enemy.
+            let offset = enemy.position() - start;
+            let along = offset.dot(direction);
+            let closest = offset - direction * along;
+            if along < 0.0 || closest.length() > radius {
+                return None;
+            }
+            let distance = along - (radius * radius - closest.length_squared()).sqrt();
+            (distance <= length).then_some((distance.max(0.0), enemy.as_ref()))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Radius of a circle around the collider of the enemy.
+fn radius(enemy: &dyn Enemy) -> f32 {
+    match enemy.debug_info().shape {
+        ColliderShape::Square(half_extents) => half_extents.length(),
+        ColliderShape::Circle(radius) => radius,
+    }
+}
+
+/// Slowly turns the arrow towards the nearest enemy while the player is not turning it.
+pub fn assist_aim(paddle: &mut Paddle, enemies: &[Box<dyn Enemy>]) {
+    if INPUT.mouse_down(&MouseButton::Left) || INPUT.mouse_down(&MouseButton::Right) {
+        return;
+    }
+    let origin = paddle.object.transform.position;
+    let Some(nearest) = enemies
+        .iter()
+        .map(|enemy| enemy.position() - origin)
+        .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+    else {
+        return;
+    };
+    let target = (nearest.y.atan2(nearest.x) + PI * 0.5).clamp(PI * 0.25, PI * 0.75);
+    let rotation = &mut paddle.arrow.transform.rotation;
+    let step = ASSIST_SPEED * TIME.delta_time() as f32;
+    *rotation += (target - *rotation).clamp(-step, step);
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use super::{
+    tween::{Easing, RealClock, Tween},
+    Layers,
+};
+use crate::game::GameScene;
+use anyhow::Result;
+use let_engine::prelude::*;
+use rand::random;
+
+/// How the screen gets covered and revealed when switching scenes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    /// Switches without any animation.
+    Instant,
+    /// Fades to black and back taking the given time for each half.
+    Fade(Duration),
+    /// Slides a black curtain over the screen from the left and off to the right.
+    Wipe(Duration),
+    /// Fills the screen with black tiles in random order and removes them again.
+    Dissolve(Duration),
+}
+
+/// What the game has to do after updating a transition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionState {
+    /// The transition is still playing.
+    Playing,
+    /// The screen is fully covered, so the next scene should be loaded now.
+    Covered(GameScene),
+    /// The new scene is fully revealed and the transition can be removed.
+    Finished,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Out,
+    Hold,
+    In,
+}
+
+enum Cover {
+    Fade(Object),
+    Wipe { object: Object, width: f32 },
+    Dissolve(Vec<(Object, f32)>),
+}
+
+const DISSOLVE_COLUMNS: usize = 16;
+const DISSOLVE_ROWS: usize = 9;
+
+/// A running transition between two scenes drawn on the overlay layer.
+pub struct SceneTransition {
+    target: Option<GameScene>,
+    phase: Phase,
+    coverage: Tween<f32>,
+    // The outgoing scene may have paused the game, so this runs in real time.
+    clock: RealClock,
+    cover: Cover,
+}
+
+impl SceneTransition {
+    /// Covers the screen, lets the game load `target` and reveals it again.
+    pub fn new(layers: &Layers, transition: Transition, target: GameScene) -> Result<Self> {
+        let mut new = Self::with_cover(layers, transition)?;
+        new.target = Some(target);
+        new.phase = Phase::Out;
+        new.coverage = Tween::new(0.0, 1.0, new.coverage.duration()).easing(Easing::QuadIn);
+        new.apply()?;
+        Ok(new)
+    }
+
+    /// Starts fully covered and only reveals the current scene.
+    pub fn reveal(layers: &Layers, transition: Transition) -> Result<Self> {
+        let mut new = Self::with_cover(layers, transition)?;
+        new.apply()?;
+        Ok(new)
+    }
+
+    fn with_cover(layers: &Layers, transition: Transition) -> Result<Self> {
+        let layer = &layers.overlay;
+        let half_size = SETTINGS
+            .window()
+            .map(|window| CameraScaling::KeepVertical.scale(window.inner_size()))
+            .unwrap_or(vec2(16.0 / 9.0, 1.0));
+
+        let black_square = |size: Vec2, position: Vec2| -> Result<Object> {
+            Ok(NewObjectBuilder::default()
+                .appearance(
+                    Appearance::new()
+                        .color(Color::BLACK)
+                        .model(Some(Model::Square))
+                        .transform(Transform::default().size(size)),
+                )
+                .transform(Transform::default().position(position))
+                .build()?
+                .init(layer)?)
+        };
+
+        let (cover, duration) = match transition {
+            Transition::Instant => (
+                Cover::Fade(black_square(vec2(10.0, 10.0), Vec2::ZERO)?),
+                Duration::ZERO,
+            ),
+            Transition::Fade(duration) => (
+                Cover::Fade(black_square(vec2(10.0, 10.0), Vec2::ZERO)?),
+                duration,
+            ),
+            Transition::Wipe(duration) => (
+                Cover::Wipe {
+                    object: black_square(half_size, Vec2::ZERO)?,
+                    width: half_size.x * 2.0,
+                },
+                duration,
+            ),
+            Transition::Dissolve(duration) => {
+                let tile = half_size / vec2(DISSOLVE_COLUMNS as f32, DISSOLVE_ROWS as f32);
+                let mut tiles = Vec::with_capacity(DISSOLVE_COLUMNS * DISSOLVE_ROWS);
+                for row in 0..DISSOLVE_ROWS {
+                    for column in 0..DISSOLVE_COLUMNS {
+                        let position = -half_size
+                            + tile * vec2(column as f32 * 2.0 + 1.0, row as f32 * 2.0 + 1.0);
+                        tiles.push((black_square(tile, position)?, random::<f32>()));
+                    }
+                }
+                (Cover::Dissolve(tiles), duration)
+            }
+        };
+
+        Ok(Self {
+            target: None,
+            phase: Phase::In,
+            coverage: Tween::new(1.0, 0.0, duration.as_secs_f32()).easing(Easing::QuadOut),
+            clock: RealClock::new(),
+            cover,
+        })
+    }
+
+    /// Whether this transition is still going to switch the scene.
+    pub fn is_switching(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn update(&mut self) -> Result<TransitionState> {
+        let delta = self.clock.delta();
+        let state = match self.phase {
+            Phase::Out => {
+                self.coverage.advance(delta);
+                if self.coverage.finished() {
+                    // Hold for a frame so the fully covered screen gets drawn before loading.
+                    self.phase = Phase::Hold;
+                }
+                TransitionState::Playing
+            }
+            Phase::Hold => {
+                self.phase = Phase::In;
+                self.coverage =
+                    Tween::new(1.0, 0.0, self.coverage.duration()).easing(Easing::QuadOut);
+                // Loading the scene takes a while, which should not skip the reveal.
+                self.clock = RealClock::new();
+                match self.target.take() {
+                    Some(scene) => TransitionState::Covered(scene),
+                    None => TransitionState::Playing,
+                }
+            }
+            Phase::In => {
+                self.coverage.advance(delta);
+                if self.coverage.finished() {
+                    TransitionState::Finished
+                } else {
+                    TransitionState::Playing
+                }
+            }
+        };
+        self.apply()?;
+        Ok(state)
+    }
+
+    /// Updates the cover objects to the current coverage.
+    fn apply(&mut self) -> Result<()> {
+        let coverage = self.coverage.value();
+        match &mut self.cover {
+            Cover::Fade(object) => {
+                object.appearance.get_color_mut().set_a(coverage);
+                object.sync()?;
+            }
+            Cover::Wipe { object, width } => {
+                object.transform.position.x = if self.phase == Phase::In {
+                    (1.0 - coverage) * *width
+                } else {
+                    (coverage - 1.0) * *width
+                };
+                object.sync()?;
+            }
+            Cover::Dissolve(tiles) => {
+                for (object, threshold) in tiles {
+                    let visible = *threshold < coverage;
+                    if object.appearance.get_visible() != visible {
+                        object.appearance.set_visible(visible);
+                        object.sync()?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(self) {
+        match self.cover {
+            Cover::Fade(object) | Cover::Wipe { object, .. } => {
+                let _ = object.remove();
+            }
+            Cover::Dissolve(tiles) => {
+                for (object, _) in tiles {
+                    let _ = object.remove();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! Interpolation of values over time for fades and movement.
+use std::{collections::VecDeque, f32::consts::PI, time::Instant};
+
+use let_engine::prelude::*;
+
+/// Curves that shape how a tween progresses from start to end.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+    ExpoOut,
+    BackOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` from 0 to 1 onto this curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) * 0.5
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) * 0.5
+                }
+            }
+            Easing::SineInOut => -((PI * t).cos() - 1.0) * 0.5,
+            Easing::ExpoOut => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2.0f32.powf(-10.0 * t)
+                }
+            }
+            Easing::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// Values that can be interpolated by a tween.
+pub trait Tweenable: Copy {
+    fn interpolate(self, to: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Tweenable for Vec2 {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+impl Tweenable for Color {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+/// Interpolates a value from one point to another in a given amount of seconds.
+#[derive(Clone, Copy, Debug)]
+pub struct Tween<T: Tweenable> {
+    from: T,
+    to: T,
+    duration: f32,
+    easing: Easing,
+    elapsed: f32,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing: Easing::Linear,
+            elapsed: 0.0,
+        }
+    }
+
+    /// A tween that is already finished resting at `value`.
+    pub fn resting(value: T) -> Self {
+        Self::new(value, value, 0.0)
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Advances the tween by the delta time of the game, so it halts while the game is paused.
+    pub fn update(&mut self) -> T {
+        self.advance(TIME.delta_time() as f32)
+    }
+
+    /// Advances the tween by `delta` seconds and returns the new value.
+    pub fn advance(&mut self, delta: f32) -> T {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+        self.value()
+    }
+
+    pub fn value(&self) -> T {
+        if self.duration <= 0.0 {
+            return self.to;
+        }
+        self.from
+            .interpolate(self.to, self.easing.apply(self.elapsed / self.duration))
+    }
+
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Starts a new tween from the current value to `to` keeping the duration and easing.
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = 0.0;
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Seconds this tween is still going to run.
+    fn remaining(&self) -> f32 {
+        self.duration - self.elapsed
+    }
+}
+
+enum Step<T: Tweenable> {
+    Tween(Tween<T>),
+    Wait(f32),
+    Call(Box<dyn FnOnce() + Send + Sync>),
+}
+
+/// A chain of tweens, waits and callbacks that run one after another.
+pub struct Sequence<T: Tweenable> {
+    value: T,
+    end: T,
+    steps: VecDeque<Step<T>>,
+}
+
+impl<T: Tweenable> Sequence<T> {
+    pub fn new(start: T) -> Self {
+        Self {
+            value: start,
+            end: start,
+            steps: VecDeque::new(),
+        }
+    }
+
+    /// Tweens from where the previous step ended to `to`.
+    pub fn then(mut self, to: T, duration: f32, easing: Easing) -> Self {
+        self.steps.push_back(Step::Tween(
+            Tween::new(self.end, to, duration).easing(easing),
+        ));
+        self.end = to;
+        self
+    }
+
+    /// Holds the current value for `duration` seconds.
+    pub fn wait(mut self, duration: f32) -> Self {
+        self.steps.push_back(Step::Wait(duration));
+        self
+    }
+
+    /// Calls `callback` once all steps before it are done.
+    pub fn call(mut self, callback: impl FnOnce() + Send + Sync + 'static) -> Self {
+        self.steps.push_back(Step::Call(Box::new(callback)));
+        self
+    }
+
+    /// Advances the sequence by the delta time of the game.
+    pub fn update(&mut self) -> T {
+        self.advance(TIME.delta_time() as f32)
+    }
+
+    /// Advances the sequence by `delta` seconds carrying leftover time into the next steps.
+    pub fn advance(&mut self, mut delta: f32) -> T {
+        while let Some(step) = self.steps.front_mut() {
+            match step {
+                Step::Tween(tween) => {
+                    let remaining = tween.remaining();
+                    self.value = tween.advance(delta);
+                    if !tween.finished() {
+                        break;
+                    }
+                    delta -= remaining;
+                }
+                Step::Wait(duration) => {
+                    if delta < *duration {
+                        *duration -= delta;
+                        break;
+                    }
+                    delta -= *duration;
+                }
+                Step::Call(_) => {
+                    if let Some(Step::Call(callback)) = self.steps.pop_front() {
+                        callback();
+                    }
+                    continue;
+                }
+            }
+            self.steps.pop_front();
+        }
+        self.value
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Measures real time between updates for tweens that have to keep running while the game is paused.
+#[derive(Clone, Copy, Debug)]
+pub struct RealClock {
+    last: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            last: Instant::now(),
+        }
+    }
+
+    /// Seconds since the last call.
+    pub fn delta(&mut self) -> f32 {
+        let now = Instant::now();
+        let delta = (now - self.last).as_secs_f32();
+        self.last = now;
+        delta
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    #[test]
+    fn calls_after_the_steps_before() {
+        let called = Arc::new(AtomicBool::new(false));
+        let flag = called.clone();
+        let mut sequence = Sequence::new(0.0)
+            .then(1.0, 1.0, Easing::Linear)
+            .wait(0.5)
+            .call(move || flag.store(true, Ordering::Relaxed));
+
+        sequence.advance(1.2);
+        assert!(!called.load(Ordering::Relaxed));
+        assert_eq!(sequence.advance(0.4), 1.0);
+        assert!(called.load(Ordering::Relaxed));
+        assert!(sequence.finished());
+    }
+}